@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::markdown::{
+    DirCount, RenderPlan, Trimmed, file_lines, is_within, render_budget_notes,
+    render_tree_section, render_with_plan, summarized_heading,
+};
+use crate::{FileResult, RenderConfig, RenderProfile, RunOutput, Section};
+
+/// Tree directories list at most this many files at each collapse step.
+const TREE_FILE_LIMITS: [usize; 4] = [20, 10, 5, 0];
+
+/// Rough token count for budgeting: about four characters per token.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Characters `lines` take in the rendered document, newlines included.
+fn lines_len(lines: &[String]) -> usize {
+    lines.iter().map(|line| line.chars().count() + 1).sum()
+}
+
+/// Renders Markdown that fits `max_tokens`, degrading step by step until it
+/// does: shallower tree, collapsed tree directories, cheaper per-file
+/// profiles (lowest priority first), then whole directories as counts.
+///
+/// Each step adjusts a running character count by what it changed, measured
+/// per section or per file; only a step whose estimate fits is rendered in
+/// full to confirm.
+pub(crate) fn render_within_budget(
+    output: &RunOutput,
+    config: RenderConfig,
+    max_tokens: usize,
+) -> String {
+    let mut plan = RenderPlan {
+        budget: Some(max_tokens),
+        ..RenderPlan::default()
+    };
+    let rendered = render_with_plan(output, config, &plan);
+    if estimate_tokens(&rendered) <= max_tokens {
+        return rendered;
+    }
+    let mut trimmed = Trimmed::default();
+    let mut estimate = Estimate {
+        output,
+        config,
+        max_tokens,
+        chars: rendered.chars().count(),
+        notes: lines_len(&render_budget_notes(&plan, &trimmed)),
+    };
+
+    // Tree steps only pay off when the tree is rendered.
+    let has_tree = config.sections.contains(Section::Tree);
+    let tree_len = |plan: &RenderPlan| lines_len(&render_tree_section(output, config, plan));
+    let deepest = output
+        .repo_entries
+        .iter()
+        .filter(|_| has_tree)
+        .map(|entry| entry.depth)
+        .max()
+        .unwrap_or(0);
+    for depth in (1..deepest).rev() {
+        let before = tree_len(&plan);
+        plan.tree_depth = Some(depth);
+        estimate.replace(before, tree_len(&plan));
+        if let Some(rendered) = estimate.fits(&plan, &trimmed) {
+            return rendered;
+        }
+    }
+
+    for limit in TREE_FILE_LIMITS.into_iter().filter(|_| has_tree) {
+        let before = tree_len(&plan);
+        plan.tree_max_files = Some(limit);
+        estimate.replace(before, tree_len(&plan));
+        if let Some(rendered) = estimate.fits(&plan, &trimmed) {
+            return rendered;
+        }
+    }
+
+    let file_len = |file: &FileResult, profile| lines_len(&file_lines(file, config, profile));
+    let files = files_by_priority(output);
+    let chunk = files.len().div_ceil(10).max(1);
+    for profile in [RenderProfile::Balanced, RenderProfile::Compact] {
+        if profile >= config.profile {
+            continue;
+        }
+        for batch in files.chunks(chunk) {
+            for file in batch {
+                let previous = plan.profile_for(&file.file_path, config.profile);
+                estimate.replace(file_len(file, previous), file_len(file, profile));
+                plan.profiles.insert(file.file_path.clone(), profile);
+                trimmed.count_profile(previous, -1);
+                trimmed.count_profile(profile, 1);
+            }
+            if let Some(rendered) = estimate.fits(&plan, &trimmed) {
+                return rendered;
+            }
+        }
+    }
+
+    let dirs = dirs_by_priority(output);
+    let count_lines = dirs
+        .iter()
+        .map(|(dir, _, count)| (dir.as_str(), lines_len(&[count.line(dir)])))
+        .collect::<HashMap<_, _>>();
+    let mut hidden = vec![false; output.file_results.len()];
+    for (dir, dir_files, _) in &dirs {
+        if plan.is_summarized(dir) {
+            continue;
+        }
+        for &idx in dir_files {
+            if hidden[idx] {
+                continue;
+            }
+            let file = &output.file_results[idx];
+            let profile = plan.profile_for(&file.file_path, config.profile);
+            estimate.replace(file_len(file, profile), 0);
+            hidden[idx] = true;
+            trimmed.summarized += 1;
+        }
+        if plan.summarized.is_empty() {
+            estimate.replace(0, lines_len(&summarized_heading()));
+        }
+        plan.summarized.retain(|nested| {
+            let keep = !is_within(nested, dir);
+            if !keep {
+                estimate.replace(count_lines[nested.as_str()], 0);
+            }
+            keep
+        });
+        estimate.replace(0, count_lines[dir.as_str()]);
+        plan.summarized.push(dir.clone());
+        plan.summarized.sort();
+        if let Some(rendered) = estimate.fits(&plan, &trimmed) {
+            return rendered;
+        }
+    }
+
+    plan.over_budget = true;
+    render_with_plan(output, config, &plan)
+}
+
+/// Running size of the document the current plan renders.
+struct Estimate<'a> {
+    output: &'a RunOutput,
+    config: RenderConfig,
+    max_tokens: usize,
+    chars: usize,
+    /// Size of the budget notes, which every step rewrites.
+    notes: usize,
+}
+
+impl Estimate<'_> {
+    /// Accounts for a part of the document going from `before` to `after` characters.
+    fn replace(&mut self, before: usize, after: usize) {
+        self.chars = (self.chars + after).saturating_sub(before);
+    }
+
+    /// The rendered document, when it fits. Shared headings make the estimate
+    /// approximate, so a miss resynchronises it with the real size.
+    fn fits(&mut self, plan: &RenderPlan, trimmed: &Trimmed) -> Option<String> {
+        let notes = lines_len(&render_budget_notes(plan, trimmed));
+        self.replace(self.notes, notes);
+        self.notes = notes;
+        if self.chars.div_ceil(4) > self.max_tokens {
+            return None;
+        }
+
+        let rendered = render_with_plan(self.output, self.config, plan);
+        self.chars = rendered.chars().count();
+        (estimate_tokens(&rendered) <= self.max_tokens).then_some(rendered)
+    }
+}
+
+fn export_count(file: &FileResult) -> usize {
+    file.function_exports.len()
+        + file.type_exports.len()
+        + file.component_props.len()
+        + file.custom_exports.len()
+}
+
+fn path_depth(path: &str) -> usize {
+    path.matches('/').count()
+}
+
+/// Files to degrade first: least important, then deeper paths, then those
+/// exporting less.
+fn files_by_priority(output: &RunOutput) -> Vec<&FileResult> {
+    let mut files = output.file_results.iter().collect::<Vec<_>>();
+    files.sort_by(|a, b| {
+        a.importance
+            .cmp(&b.importance)
+            .then(path_depth(&b.file_path).cmp(&path_depth(&a.file_path)))
+            .then(export_count(a).cmp(&export_count(b)))
+            .then(a.file_path.cmp(&b.file_path))
+    });
+    files
+}
+
+/// Every directory holding scanned files, with the indices of the files under
+/// it and their counts: least important (summed over its files), deepest and
+/// smallest first. Root files are never summarised.
+fn dirs_by_priority(output: &RunOutput) -> Vec<(String, Vec<usize>, DirCount)> {
+    let mut weights: BTreeMap<&str, (u32, usize, Vec<usize>, DirCount)> = BTreeMap::new();
+    for (idx, file) in output.file_results.iter().enumerate() {
+        let mut path = file.file_path.as_str();
+        while let Some((dir, _)) = path.rsplit_once('/') {
+            let (importance, exports, files, count) = weights.entry(dir).or_default();
+            *importance += file.importance;
+            *exports += export_count(file);
+            files.push(idx);
+            count.add(file);
+            path = dir;
+        }
+    }
+
+    let mut dirs = weights.into_iter().collect::<Vec<_>>();
+    dirs.sort_by(
+        |(a, (a_importance, a_exports, _, _)), (b, (b_importance, b_exports, _, _))| {
+            a_importance
+                .cmp(b_importance)
+                .then(path_depth(b).cmp(&path_depth(a)))
+                .then(a_exports.cmp(b_exports))
+                .then(a.cmp(b))
+        },
+    );
+    dirs.into_iter()
+        .map(|(dir, (_, _, files, count))| (dir.to_string(), files, count))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FileResult, FunctionExport, RenderConfig, RenderProfile, RepoEntry, RunOutput};
+
+    use super::{estimate_tokens, render_within_budget};
+
+    fn file(path: &str, functions: usize) -> FileResult {
+        FileResult {
+            file_path: path.to_string(),
+            function_exports: (0..functions)
+                .map(|idx| FunctionExport {
+                    name: format!("handler{idx}"),
+                    signature: format!(
+                        "handler{idx}(request: Request, options: Options): Response"
+                    ),
+                    file_path: path.to_string(),
+                    line: idx + 1,
+                    doc: None,
+                    deprecated: None,
+                    overloads: vec![],
+                    ambient: false,
+                    namespace: None,
+                    importance: 0,
+                })
+                .collect(),
+            type_exports: vec![],
+            component_props: vec![],
+            custom_exports: vec![],
+            package: None,
+            parse_error: None,
+            importance: 0,
+        }
+    }
+
+    fn large_output() -> RunOutput {
+        let mut file_results = vec![file("src/index.ts", 3)];
+        for idx in 0..30 {
+            file_results.push(file(&format!("src/routes/deep/r{idx}.ts"), 4));
+        }
+        let mut repo_entries = vec![
+            ("src", true),
+            ("src/routes", true),
+            ("src/routes/deep", true),
+        ]
+        .into_iter()
+        .map(|(path, is_dir)| RepoEntry {
+            path: path.to_string(),
+            is_dir,
+            depth: path.split('/').count(),
+        })
+        .collect::<Vec<_>>();
+        repo_entries.extend(file_results.iter().map(|f| RepoEntry {
+            path: f.file_path.clone(),
+            is_dir: false,
+            depth: f.file_path.split('/').count(),
+        }));
+
+        RunOutput {
+            root_path: "/tmp/repo".to_string(),
+            repo_entries,
+            summary: Default::default(),
+            file_results,
+            walk_errors: vec![],
+        }
+    }
+
+    fn detailed(max_tokens: usize) -> RenderConfig {
+        RenderConfig {
+            profile: RenderProfile::Detailed,
+            max_tokens: Some(max_tokens),
+            ..RenderConfig::default()
+        }
+    }
+
+    #[test]
+    fn estimates_four_characters_per_token() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn leaves_output_alone_when_it_fits() {
+        let output = large_output();
+        let rendered = render_within_budget(&output, detailed(100_000), 100_000);
+
+        assert!(!rendered.contains("## Budget Notes"));
+        assert!(rendered.contains("@L1"));
+    }
+
+    #[test]
+    fn degrades_until_the_budget_fits() {
+        let output = large_output();
+        let full = render_within_budget(&output, detailed(100_000), 100_000);
+        let budget = estimate_tokens(&full) / 2;
+
+        let rendered = render_within_budget(&output, detailed(budget), budget);
+
+        assert!(estimate_tokens(&rendered) <= budget, "{rendered}");
+        assert!(rendered.contains("## Budget Notes"));
+        assert!(rendered.contains("Tree directories show file counts only."));
+        assert!(rendered.contains("files rendered with the compact profile."));
+        assert!(
+            rendered
+                .contains("### `src/index.ts`\n- `handler0(request: Request, options: Options)`\n"),
+            "the shallow root file is downgraded last: {rendered}"
+        );
+    }
+
+    #[test]
+    fn summarises_directories_as_a_last_resort() {
+        let output = large_output();
+        let rendered = render_within_budget(&output, detailed(400), 400);
+
+        assert!(rendered.contains("# Summarised Directories"));
+        assert!(rendered.contains("- `src/routes/deep/`: 30 files, 120 functions, 0 types"));
+        assert!(!rendered.contains("### `src/routes/deep/r0.ts`"));
+        assert!(rendered.contains("1 directory (30 files) summarised into counts."));
+    }
+}
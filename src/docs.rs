@@ -0,0 +1,202 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct DocComment {
+    pub summary: Option<String>,
+    pub params: Vec<DocParam>,
+    pub returns: Option<String>,
+    pub deprecated: Option<String>,
+    pub internal: bool,
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DocParam {
+    pub name: String,
+    pub description: String,
+}
+
+/// Parses a `/** ... */` block into its summary and the tags we care about.
+/// Returns `None` for plain `//` or `/* */` comments.
+pub fn parse_doc_comment(raw: &str) -> Option<DocComment> {
+    let body = raw.trim().strip_prefix("/**")?.strip_suffix("*/")?;
+    let lines = body.lines().map(strip_comment_gutter).collect::<Vec<_>>();
+
+    let mut doc = DocComment::default();
+    let mut summary = Vec::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+
+    for line in lines {
+        if let Some(tag_line) = line.trim_start().strip_prefix('@') {
+            if let Some((tag, text)) = current.take() {
+                apply_tag(&mut doc, &tag, text);
+            }
+            let (tag, rest) = tag_line
+                .split_once(char::is_whitespace)
+                .unwrap_or((tag_line, ""));
+            current = Some((tag.to_string(), vec![rest.to_string()]));
+            continue;
+        }
+
+        match current.as_mut() {
+            Some((_, text)) => text.push(line.to_string()),
+            None => summary.push(line.trim().to_string()),
+        }
+    }
+
+    if let Some((tag, text)) = current.take() {
+        apply_tag(&mut doc, &tag, text);
+    }
+
+    let first_paragraph = summary
+        .iter()
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+    if !first_paragraph.is_empty() {
+        doc.summary = Some(first_paragraph);
+    }
+
+    Some(doc)
+}
+
+fn strip_comment_gutter(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    match trimmed.strip_prefix('*') {
+        Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+        None => trimmed,
+    }
+}
+
+fn apply_tag(doc: &mut DocComment, tag: &str, text: Vec<String>) {
+    match tag {
+        "param" => {
+            if let Some(param) = parse_param(&join_inline(&text)) {
+                doc.params.push(param);
+            }
+        }
+        "returns" | "return" => {
+            let value = join_inline(&text);
+            doc.returns = Some(strip_type_annotation(&value).to_string());
+        }
+        "deprecated" => doc.deprecated = Some(join_inline(&text)),
+        "internal" => doc.internal = true,
+        "example" => {
+            let example = text.join("\n").trim().to_string();
+            if !example.is_empty() {
+                doc.examples.push(example);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_param(text: &str) -> Option<DocParam> {
+    let rest = strip_type_annotation(text);
+    let (name, description) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if name.is_empty() {
+        return None;
+    }
+
+    let description = description.trim_start();
+    let description = description.strip_prefix("- ").unwrap_or(description).trim();
+
+    Some(DocParam {
+        name: name.trim_matches(|c| c == '[' || c == ']').to_string(),
+        description: description.to_string(),
+    })
+}
+
+/// Drops a leading JSDoc `{Type}` annotation, which TS signatures already carry.
+fn strip_type_annotation(text: &str) -> &str {
+    let text = text.trim_start();
+    if !text.starts_with('{') {
+        return text;
+    }
+
+    let mut depth = 0usize;
+    for (idx, ch) in text.char_indices() {
+        if ch == '{' {
+            depth += 1;
+        } else if ch == '}' {
+            depth = depth.saturating_sub(1);
+            if depth == 0 {
+                return text[idx + 1..].trim_start();
+            }
+        }
+    }
+
+    text
+}
+
+fn join_inline(text: &[String]) -> String {
+    text.iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DocParam, parse_doc_comment};
+
+    #[test]
+    fn parses_summary_and_tags() {
+        let raw = r#"/**
+ * Normalizes user input.
+ * Trims and lowercases.
+ *
+ * Longer description that is not part of the summary.
+ * @param {string} input - raw value
+ * @param [fallback] used when empty
+ * @returns {string} the cleaned value
+ * @deprecated Use cleanInput instead
+ * @internal
+ * @example
+ * normalize(" A ")
+ * // => "a"
+ */"#;
+        let doc = parse_doc_comment(raw).expect("doc");
+
+        assert_eq!(
+            doc.summary.as_deref(),
+            Some("Normalizes user input. Trims and lowercases.")
+        );
+        assert_eq!(
+            doc.params,
+            vec![
+                DocParam {
+                    name: "input".to_string(),
+                    description: "raw value".to_string(),
+                },
+                DocParam {
+                    name: "fallback".to_string(),
+                    description: "used when empty".to_string(),
+                },
+            ]
+        );
+        assert_eq!(doc.returns.as_deref(), Some("the cleaned value"));
+        assert_eq!(doc.deprecated.as_deref(), Some("Use cleanInput instead"));
+        assert!(doc.internal);
+        assert_eq!(
+            doc.examples,
+            vec!["normalize(\" A \")\n// => \"a\"".to_string()]
+        );
+    }
+
+    #[test]
+    fn ignores_non_doc_comments() {
+        assert!(parse_doc_comment("// plain").is_none());
+        assert!(parse_doc_comment("/* block */").is_none());
+        assert_eq!(
+            parse_doc_comment("/** One liner */")
+                .expect("doc")
+                .summary
+                .as_deref(),
+            Some("One liner")
+        );
+    }
+}
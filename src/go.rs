@@ -0,0 +1,324 @@
+use std::path::Path;
+
+use tree_sitter::{Node, Parser};
+
+use crate::docs::DocComment;
+use crate::language::LanguageExtractor;
+use crate::parser::{
+    ExtractedExports, ExtractedFunction, ExtractedType, collect_syntax_errors, text_for,
+};
+use crate::walker::WalkOptions;
+
+/// Indexes the exported (capitalized) declarations of a Go file. The package
+/// name is recorded so files of one package are rendered together.
+pub struct GoExportParser {
+    parser: Parser,
+}
+
+impl GoExportParser {
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_go::LANGUAGE.into())
+            .map_err(|err| format!("{err}"))?;
+
+        Ok(Self { parser })
+    }
+
+    pub fn extract_exports(&mut self, source: &str) -> Result<ExtractedExports, String> {
+        let tree = self
+            .parser
+            .parse(source, None)
+            .ok_or_else(|| "failed to parse file".to_string())?;
+        let root = tree.root_node();
+
+        let mut exports = ExtractedExports::default();
+        let mut cursor = root.walk();
+        for declaration in root.named_children(&mut cursor) {
+            if declaration.has_error() {
+                continue;
+            }
+
+            match declaration.kind() {
+                "package_clause" => {
+                    exports.package = first_named_child(declaration)
+                        .map(|name| text_for(name, source).to_string());
+                }
+                "function_declaration" => {
+                    exports
+                        .functions
+                        .extend(function_export(declaration, None, source));
+                }
+                "method_declaration" => {
+                    let receiver = declaration
+                        .child_by_field_name("receiver")
+                        .and_then(|receiver| receiver_type(receiver, source));
+                    if let Some(receiver) = receiver.filter(|name| is_exported(name)) {
+                        exports.functions.extend(function_export(
+                            declaration,
+                            Some(receiver),
+                            source,
+                        ));
+                    }
+                }
+                "type_declaration" => {
+                    exports.types.extend(type_exports(declaration, source));
+                }
+                _ => {}
+            }
+        }
+
+        if root.has_error() {
+            collect_syntax_errors(root, source, &mut exports.syntax_errors);
+        }
+
+        exports.sort();
+        Ok(exports)
+    }
+}
+
+impl LanguageExtractor for GoExportParser {
+    fn name(&self) -> &str {
+        "go"
+    }
+
+    /// `_test.go` files hold tests and examples, not the package API.
+    fn handles(&self, path: &Path, _options: &WalkOptions) -> bool {
+        path.extension().is_some_and(|ext| ext == "go")
+            && !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with("_test.go"))
+    }
+
+    fn extract(&mut self, source: &str, _path: &Path) -> Result<ExtractedExports, String> {
+        self.extract_exports(source)
+    }
+}
+
+fn is_exported(name: &str) -> bool {
+    name.chars().next().is_some_and(char::is_uppercase)
+}
+
+/// `Map[T any](xs []T) []T`, or `Server.Handle(w io.Writer) error` for methods.
+fn function_export(
+    node: Node<'_>,
+    receiver: Option<&str>,
+    source: &str,
+) -> Option<ExtractedFunction> {
+    let name_node = node.child_by_field_name("name")?;
+    let name = text_for(name_node, source);
+    if !is_exported(name) {
+        return None;
+    }
+
+    let name = match receiver {
+        Some(receiver) => format!("{receiver}.{name}"),
+        None => name.to_string(),
+    };
+    let type_parameters = node
+        .child_by_field_name("type_parameters")
+        .map(|n| text_for(n, source))
+        .unwrap_or_default();
+    let parameters = node
+        .child_by_field_name("parameters")
+        .map(|n| text_for(n, source))
+        .unwrap_or("()");
+
+    let mut signature = format!("{name}{type_parameters}{parameters}");
+    if let Some(result) = node.child_by_field_name("result") {
+        signature.push(' ');
+        signature.push_str(text_for(result, source));
+    }
+
+    let (doc, deprecated) = doc_comment(node, source);
+    Some(ExtractedFunction {
+        name,
+        signature,
+        line: name_node.start_position().row + 1,
+        doc,
+        deprecated,
+        overloads: Vec::new(),
+        ambient: false,
+        namespace: None,
+    })
+}
+
+/// `(s *Server)` -> `Server`, `(l List[T])` -> `List`.
+fn receiver_type<'a>(receiver: Node<'_>, source: &'a str) -> Option<&'a str> {
+    let parameter = first_named_child(receiver)?;
+    let mut ty = parameter.child_by_field_name("type")?;
+    loop {
+        match ty.kind() {
+            "pointer_type" | "parenthesized_type" => ty = first_named_child(ty)?,
+            "generic_type" => ty = ty.child_by_field_name("type")?,
+            _ => return Some(text_for(ty, source)),
+        }
+    }
+}
+
+/// Every exported `type_spec`/`type_alias` in a `type` declaration, grouped
+/// `type ( ... )` blocks included. A lone spec takes the comment above `type`.
+fn type_exports(declaration: Node<'_>, source: &str) -> Vec<ExtractedType> {
+    let mut cursor = declaration.walk();
+    let specs = declaration
+        .named_children(&mut cursor)
+        .filter(|spec| matches!(spec.kind(), "type_spec" | "type_alias"))
+        .collect::<Vec<_>>();
+    let grouped = specs.len() > 1;
+
+    specs
+        .into_iter()
+        .filter_map(|spec| {
+            let name_node = spec.child_by_field_name("name")?;
+            let name = text_for(name_node, source);
+            if !is_exported(name) {
+                return None;
+            }
+
+            let (doc, deprecated) = doc_comment(if grouped { spec } else { declaration }, source);
+            Some(ExtractedType {
+                name: name.to_string(),
+                signature: None,
+                line: name_node.start_position().row + 1,
+                doc,
+                deprecated,
+                ambient: false,
+                namespace: None,
+            })
+        })
+        .collect()
+}
+
+/// The `//` comment lines directly above a declaration, plus the text of a
+/// `Deprecated:` paragraph, following Go doc conventions.
+fn doc_comment(node: Node<'_>, source: &str) -> (Option<DocComment>, Option<String>) {
+    let mut lines = Vec::new();
+    let mut next_row = node.start_position().row;
+    let mut current = node.prev_sibling();
+
+    while let Some(comment) = current.filter(|sibling| sibling.kind() == "comment") {
+        if comment.end_position().row + 1 != next_row {
+            break;
+        }
+        let Some(line) = text_for(comment, source).strip_prefix("//") else {
+            break;
+        };
+        lines.push(line.trim().to_string());
+        next_row = comment.start_position().row;
+        current = comment.prev_sibling();
+    }
+    lines.reverse();
+
+    let paragraphs = lines
+        .split(|line| line.is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| paragraph.join(" "))
+        .collect::<Vec<_>>();
+    let deprecated = paragraphs.iter().find_map(|paragraph| {
+        paragraph
+            .strip_prefix("Deprecated:")
+            .map(|hint| hint.trim().to_string())
+    });
+    let doc = paragraphs.first().map(|summary| DocComment {
+        summary: Some(summary.clone()),
+        deprecated: deprecated.clone(),
+        ..DocComment::default()
+    });
+
+    (doc, deprecated)
+}
+
+fn first_named_child(node: Node<'_>) -> Option<Node<'_>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor).next()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::language::LanguageExtractor;
+    use crate::walker::WalkOptions;
+
+    use super::GoExportParser;
+
+    #[test]
+    fn skips_test_files() {
+        let parser = GoExportParser::new().expect("parser");
+        let options = WalkOptions::default();
+
+        assert!(parser.handles(Path::new("svc/api/server.go"), &options));
+        assert!(!parser.handles(Path::new("svc/api/server_test.go"), &options));
+    }
+
+    #[test]
+    fn extracts_exported_declarations() {
+        let mut parser = GoExportParser::new().expect("parser");
+        let source = r#"// Package api serves things.
+package api
+
+// Server handles requests.
+//
+// Deprecated: use NewServer.
+type Server struct{ addr string }
+
+type (
+	// Handler serves one route.
+	Handler interface{ Serve() error }
+	ID      = string
+	private int
+)
+
+// Handle serves one request.
+func (s *Server) Handle(w io.Writer, r *Request) (int, error) { return 0, nil }
+
+func (p *private) Hidden() {}
+
+func Map[T any, U any](xs []T, f func(T) U) []U { return nil }
+
+func helper() {}
+"#;
+        let exports = parser.extract_exports(source).expect("extract");
+
+        assert_eq!(exports.package.as_deref(), Some("api"));
+
+        let functions = exports
+            .functions
+            .iter()
+            .map(|f| f.signature.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            functions,
+            vec![
+                "Server.Handle(w io.Writer, r *Request) (int, error)",
+                "Map[T any, U any](xs []T, f func(T) U) []U",
+            ]
+        );
+        assert_eq!(
+            exports.functions[0]
+                .doc
+                .as_ref()
+                .and_then(|doc| doc.summary.as_deref()),
+            Some("Handle serves one request.")
+        );
+
+        let types = exports
+            .types
+            .iter()
+            .map(|t| (t.name.as_str(), t.line))
+            .collect::<Vec<_>>();
+        assert_eq!(types, vec![("Server", 7), ("Handler", 11), ("ID", 12)]);
+        assert_eq!(
+            exports.types[0].deprecated.as_deref(),
+            Some("use NewServer.")
+        );
+        assert_eq!(
+            exports.types[1]
+                .doc
+                .as_ref()
+                .and_then(|doc| doc.summary.as_deref()),
+            Some("Handler serves one route.")
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+use crate::FileResult;
+use crate::parser::ExtractedImport;
+
+/// Points per other file importing this one.
+const FAN_IN_POINTS: u32 = 3;
+/// Points for being re-exported from a barrel (`export * from "./x"`).
+const BARREL_POINTS: u32 = 4;
+/// Points for an export with a doc comment; a file gets them if any export has one.
+const DOC_POINTS: u32 = 2;
+/// How many commits back `git log` is read for recency.
+const RECENT_COMMITS: usize = 200;
+
+/// Extensions tried, in order, when resolving an extensionless import.
+const RESOLVE_EXTENSIONS: [&str; 10] = [
+    "ts", "tsx", "d.ts", "js", "jsx", "mjs", "cjs", "vue", "svelte", "astro",
+];
+
+/// Scores every file and export. A file scores for import fan-in, barrel
+/// re-exports, recent git changes and documentation; its exports inherit the
+/// file score plus their own documentation points.
+pub(crate) fn rank(
+    root: &Path,
+    files: &mut [FileResult],
+    imports: &HashMap<String, Vec<ExtractedImport>>,
+) {
+    let known = files
+        .iter()
+        .map(|file| file.file_path.as_str())
+        .collect::<HashSet<_>>();
+    let mut importers: HashMap<String, HashSet<&str>> = HashMap::new();
+    let mut reexported = HashSet::new();
+    for (from, specifiers) in imports {
+        for import in specifiers {
+            let Some(target) = resolve_import(from, &import.specifier, &known) else {
+                continue;
+            };
+            if target == *from {
+                continue;
+            }
+            if import.reexport {
+                reexported.insert(target.clone());
+            }
+            importers.entry(target).or_default().insert(from.as_str());
+        }
+    }
+    let recency = git_recency(root);
+
+    for file in files.iter_mut() {
+        let fan_in = importers.get(&file.file_path).map_or(0, HashSet::len) as u32;
+        let mut base = fan_in * FAN_IN_POINTS;
+        if reexported.contains(&file.file_path) {
+            base += BARREL_POINTS;
+        }
+        base += recency
+            .get(&file.file_path)
+            .map_or(0, |&commits_ago| recency_points(commits_ago));
+
+        let mut documented = false;
+        for export in &mut file.function_exports {
+            let doc = doc_points(export.doc.as_ref());
+            documented |= doc > 0;
+            export.importance = base + doc;
+        }
+        for export in &mut file.type_exports {
+            let doc = doc_points(export.doc.as_ref());
+            documented |= doc > 0;
+            export.importance = base + doc;
+        }
+        file.importance = base + if documented { DOC_POINTS } else { 0 };
+    }
+}
+
+fn doc_points(doc: Option<&crate::docs::DocComment>) -> u32 {
+    if doc.is_some_and(|doc| doc.summary.is_some()) {
+        DOC_POINTS
+    } else {
+        0
+    }
+}
+
+/// Files touched in the last 10 commits score highest, then the last 50, then
+/// anything else within [`RECENT_COMMITS`].
+fn recency_points(commits_ago: usize) -> u32 {
+    match commits_ago {
+        0..10 => 5,
+        10..50 => 3,
+        _ => 1,
+    }
+}
+
+/// Maps root-relative paths to how many commits ago they last changed. Empty
+/// when `root` is not in a git work tree or git is unavailable.
+fn git_recency(root: &Path) -> HashMap<String, usize> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["log", "--name-only", "--relative", "--pretty=format:%x1e"])
+        .arg(format!("-n{RECENT_COMMITS}"))
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_git_log(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => HashMap::new(),
+    }
+}
+
+/// Parses `git log --name-only --pretty=format:%x1e`, newest commit first.
+fn parse_git_log(log: &str) -> HashMap<String, usize> {
+    let mut recency = HashMap::new();
+    for (commits_ago, commit) in log.split('\x1e').skip(1).enumerate() {
+        for path in commit
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            recency.entry(path.to_string()).or_insert(commits_ago);
+        }
+    }
+    recency
+}
+
+/// Resolves a relative specifier against the importing file, trying the path
+/// as written, with each source extension, TypeScript's `.js` → `.ts`
+/// mapping, and `index` files. Bare package imports are not followed.
+fn resolve_import(from: &str, specifier: &str, known: &HashSet<&str>) -> Option<String> {
+    if !specifier.starts_with('.') {
+        return None;
+    }
+
+    let mut parts = from.split('/').collect::<Vec<_>>();
+    parts.pop();
+    for segment in specifier.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            segment => parts.push(segment),
+        }
+    }
+    let base = parts.join("/");
+
+    let mut candidates = vec![base.clone()];
+    let stem = [".js", ".jsx", ".mjs", ".cjs"]
+        .iter()
+        .find_map(|ext| base.strip_suffix(ext))
+        .unwrap_or(&base);
+    candidates.extend(RESOLVE_EXTENSIONS.iter().map(|ext| format!("{stem}.{ext}")));
+    candidates.extend(
+        RESOLVE_EXTENSIONS
+            .iter()
+            .map(|ext| format!("{base}/index.{ext}")),
+    );
+
+    candidates
+        .into_iter()
+        .find(|candidate| known.contains(candidate.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::path::Path;
+
+    use super::{parse_git_log, rank, resolve_import};
+    use crate::docs::DocComment;
+    use crate::parser::ExtractedImport;
+    use crate::{FileResult, FunctionExport};
+
+    fn file(path: &str, documented: bool) -> FileResult {
+        FileResult {
+            file_path: path.to_string(),
+            function_exports: vec![FunctionExport {
+                name: "run".to_string(),
+                signature: "run()".to_string(),
+                file_path: path.to_string(),
+                line: 1,
+                doc: documented.then(|| DocComment {
+                    summary: Some("Runs.".to_string()),
+                    ..DocComment::default()
+                }),
+                deprecated: None,
+                overloads: vec![],
+                ambient: false,
+                namespace: None,
+                importance: 0,
+            }],
+            type_exports: vec![],
+            component_props: vec![],
+            custom_exports: vec![],
+            package: None,
+            parse_error: None,
+            importance: 0,
+        }
+    }
+
+    fn import(specifier: &str, reexport: bool) -> ExtractedImport {
+        ExtractedImport {
+            specifier: specifier.to_string(),
+            reexport,
+        }
+    }
+
+    #[test]
+    fn resolves_relative_specifiers() {
+        let known = HashSet::from(["src/lib/util.ts", "src/lib/index.ts", "src/app.tsx"]);
+
+        assert_eq!(
+            resolve_import("src/app.tsx", "./lib/util", &known).as_deref(),
+            Some("src/lib/util.ts")
+        );
+        assert_eq!(
+            resolve_import("src/lib/util.ts", "../app.js", &known).as_deref(),
+            Some("src/app.tsx")
+        );
+        assert_eq!(
+            resolve_import("src/app.tsx", "./lib", &known).as_deref(),
+            Some("src/lib/index.ts")
+        );
+        assert_eq!(resolve_import("src/app.tsx", "react", &known), None);
+        assert_eq!(resolve_import("src/app.tsx", "../../outside", &known), None);
+    }
+
+    #[test]
+    fn scores_fan_in_barrels_and_docs() {
+        let mut files = vec![
+            file("src/index.ts", false),
+            file("src/a.ts", false),
+            file("src/b.ts", true),
+            file("src/c.ts", false),
+        ];
+        let imports = HashMap::from([
+            ("src/index.ts".to_string(), vec![import("./a", true)]),
+            (
+                "src/b.ts".to_string(),
+                vec![import("./a", false), import("./c", false)],
+            ),
+        ]);
+
+        rank(Path::new("/nonexistent"), &mut files, &imports);
+
+        let scores = files
+            .iter()
+            .map(|f| (f.file_path.as_str(), f.importance))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scores,
+            vec![
+                ("src/index.ts", 0),
+                ("src/a.ts", 2 * 3 + 4),
+                ("src/b.ts", 2),
+                ("src/c.ts", 3),
+            ]
+        );
+        assert_eq!(files[2].function_exports[0].importance, 2);
+    }
+
+    #[test]
+    fn parses_git_log_newest_first() {
+        let log = "\x1e\nsrc/a.ts\nsrc/b.ts\n\n\x1e\nsrc/a.ts\nsrc/c.ts\n";
+        let recency = parse_git_log(log);
+
+        assert_eq!(recency.get("src/a.ts"), Some(&0));
+        assert_eq!(recency.get("src/b.ts"), Some(&0));
+        assert_eq!(recency.get("src/c.ts"), Some(&1));
+    }
+}
@@ -0,0 +1,130 @@
+use std::ops::Range;
+
+/// Opens the block `--inject` owns inside another Markdown file.
+pub const START_MARKER: &str = "<!-- context-map:start -->";
+/// Closes the managed block.
+pub const END_MARKER: &str = "<!-- context-map:end -->";
+
+/// Replaces whatever sits between the markers in `document` with `block`,
+/// keeping everything outside them byte for byte. Without markers the block
+/// is appended at the end. Unbalanced markers are an error rather than a
+/// guess, so hand-written text is never overwritten.
+///
+/// A marker counts only on a line of its own outside fenced code, so a
+/// document can mention the markers in prose or examples.
+pub fn inject_block(document: &str, block: &str) -> Result<String, String> {
+    let managed = format!("{START_MARKER}\n{block}{END_MARKER}");
+    let starts = marker_lines(document, START_MARKER);
+    let ends = marker_lines(document, END_MARKER);
+
+    match (starts.as_slice(), ends.as_slice()) {
+        ([start], [end]) if start.start < end.start => Ok(format!(
+            "{}{managed}{}",
+            &document[..start.start],
+            &document[end.end..]
+        )),
+        ([], []) => {
+            let mut injected = document.to_string();
+            if !injected.is_empty() {
+                if !injected.ends_with('\n') {
+                    injected.push('\n');
+                }
+                injected.push('\n');
+            }
+            injected.push_str(&managed);
+            injected.push('\n');
+            Ok(injected)
+        }
+        (_, []) => Err(format!("`{START_MARKER}` without `{END_MARKER}`")),
+        ([], _) => Err(format!("`{END_MARKER}` without `{START_MARKER}`")),
+        ([_], [_]) => Err(format!("`{END_MARKER}` before `{START_MARKER}`")),
+        ([_], _) => Err(format!("more than one `{END_MARKER}` marker")),
+        _ => Err(format!("more than one `{START_MARKER}` marker")),
+    }
+}
+
+/// Byte ranges of the lines holding only `marker` (newline excluded), skipping
+/// code blocks fenced with backticks or tildes.
+fn marker_lines(document: &str, marker: &str) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    let mut in_fence = false;
+
+    for line in document.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+        let trimmed = content.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence && trimmed == marker {
+            lines.push(start..start + content.len());
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{END_MARKER, START_MARKER, inject_block};
+
+    #[test]
+    fn replaces_only_the_managed_block() {
+        let document = format!(
+            "# Agents\n\nRead this first.\n\n{START_MARKER}\nold map\n{END_MARKER}\n\n## Style\nTabs.\n"
+        );
+
+        let injected = inject_block(&document, "# Exported Functions\n- `a()`\n").expect("inject");
+
+        assert_eq!(
+            injected,
+            format!(
+                "# Agents\n\nRead this first.\n\n{START_MARKER}\n# Exported Functions\n- `a()`\n{END_MARKER}\n\n## Style\nTabs.\n"
+            )
+        );
+        assert_eq!(
+            inject_block(&injected, "# Exported Functions\n- `a()`\n").expect("reinject"),
+            injected
+        );
+    }
+
+    #[test]
+    fn appends_markers_when_absent() {
+        assert_eq!(
+            inject_block("# Agents\nNotes.", "map\n").expect("inject"),
+            format!("# Agents\nNotes.\n\n{START_MARKER}\nmap\n{END_MARKER}\n")
+        );
+        assert_eq!(
+            inject_block("", "map\n").expect("inject"),
+            format!("{START_MARKER}\nmap\n{END_MARKER}\n")
+        );
+    }
+
+    #[test]
+    fn rejects_unbalanced_markers() {
+        let err =
+            inject_block(&format!("{START_MARKER}\nno end\n"), "map\n").expect_err("missing end");
+        assert!(err.contains("without"));
+
+        let reversed = format!("{END_MARKER}\n{START_MARKER}\n");
+        assert!(inject_block(&reversed, "map\n").is_err());
+    }
+
+    #[test]
+    fn ignores_markers_in_inline_and_fenced_code() {
+        let document = format!(
+            "# Setup\n\nPut the map between `{START_MARKER}` and `{END_MARKER}`:\n\n\
+             ```markdown\n{START_MARKER}\n{END_MARKER}\n```\n"
+        );
+
+        let injected = inject_block(&document, "map\n").expect("inject");
+        assert_eq!(
+            injected,
+            format!("{document}\n{START_MARKER}\nmap\n{END_MARKER}\n")
+        );
+        assert_eq!(
+            inject_block(&injected, "new map\n").expect("reinject"),
+            format!("{document}\n{START_MARKER}\nnew map\n{END_MARKER}\n")
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use crate::RunOutput;
+
+/// Serializes the full run model. Unlike Markdown, JSON is not shaped by
+/// `RenderConfig`: consumers filter it themselves.
+pub fn render_json(output: &RunOutput) -> String {
+    let json = serde_json::to_string_pretty(output).expect("run output serializes to JSON");
+    json + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Diagnostic, FileResult, ParseError, RunOutput, RunSummary};
+
+    use super::render_json;
+
+    #[test]
+    fn includes_parse_diagnostics() {
+        let output = RunOutput {
+            root_path: "/tmp/repo".to_string(),
+            repo_entries: vec![],
+            summary: RunSummary::default(),
+            file_results: vec![FileResult {
+                file_path: "src/bad.ts".to_string(),
+                function_exports: vec![],
+                type_exports: vec![],
+                component_props: vec![],
+                custom_exports: vec![],
+                package: None,
+                parse_error: Some(ParseError {
+                    message: "syntax parse error".to_string(),
+                    diagnostics: vec![Diagnostic {
+                        line: 2,
+                        column: 1,
+                        start_byte: 10,
+                        end_byte: 24,
+                        kind: "ERROR".to_string(),
+                        snippet: "export function bad( {".to_string(),
+                    }],
+                    omitted: 0,
+                }),
+                importance: 0,
+            }],
+            walk_errors: vec![],
+        };
+
+        let json = render_json(&output);
+        let value: serde_json::Value = serde_json::from_str(&json).expect("valid json");
+        let diagnostic = &value["file_results"][0]["parse_error"]["diagnostics"][0];
+
+        assert_eq!(diagnostic["line"], 2);
+        assert_eq!(diagnostic["start_byte"], 10);
+        assert_eq!(diagnostic["kind"], "ERROR");
+        assert_eq!(diagnostic["snippet"], "export function bad( {");
+    }
+}
@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use crate::parser::{ExtractedExports, TsExportParser};
+use crate::queries::UserQuery;
+use crate::walker::{SourceFile, WalkOptions};
+
+/// One language's support: which files it claims and how exports are pulled
+/// out of them. Implement this to index a language the crate does not ship.
+pub trait LanguageExtractor {
+    /// Short identifier, e.g. `"typescript"`.
+    fn name(&self) -> &str;
+
+    /// Whether `path` is a source file this extractor indexes.
+    fn handles(&self, path: &Path, options: &WalkOptions) -> bool;
+
+    /// Pulls the exports out of one file; `path` is relative to the scanned root.
+    fn extract(&mut self, source: &str, path: &Path) -> Result<ExtractedExports, String>;
+}
+
+/// The extractors a run dispatches to. When several claim a file, the one
+/// registered last wins, so library users can override built-ins.
+#[derive(Default)]
+pub struct ExtractorRegistry {
+    extractors: Vec<Box<dyn LanguageExtractor>>,
+}
+
+impl ExtractorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// TypeScript/JavaScript and component files, plus each language enabled
+    /// through cargo features.
+    pub fn builtin() -> Result<Self, String> {
+        Self::builtin_with_queries(&[])
+    }
+
+    /// [`Self::builtin`], with user `.scm` queries run on TypeScript-family files.
+    pub fn builtin_with_queries(queries: &[UserQuery]) -> Result<Self, String> {
+        let mut registry = Self::new();
+        registry.register(TsExportParser::with_queries(queries)?);
+        #[cfg(feature = "python")]
+        registry.register(crate::python::PythonExportParser::new()?);
+        #[cfg(feature = "rust")]
+        registry.register(crate::rust::RustExportParser::new()?);
+        #[cfg(feature = "go")]
+        registry.register(crate::go::GoExportParser::new()?);
+        Ok(registry)
+    }
+
+    pub fn register(&mut self, extractor: impl LanguageExtractor + 'static) {
+        self.extractors.push(Box::new(extractor));
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.extractors
+            .iter()
+            .map(|extractor| extractor.name())
+            .collect()
+    }
+
+    /// Index of the extractor that claims `path`, if any.
+    pub fn classify(&self, path: &Path, options: &WalkOptions) -> Option<usize> {
+        self.extractors
+            .iter()
+            .rposition(|extractor| extractor.handles(path, options))
+    }
+
+    /// Runs the extractor that claimed `file`, found under `root`.
+    pub fn extract(
+        &mut self,
+        file: &SourceFile,
+        root: &Path,
+        source: &str,
+    ) -> Result<ExtractedExports, String> {
+        let extractor = self
+            .extractors
+            .get_mut(file.extractor)
+            .ok_or_else(|| format!("no extractor registered at index {}", file.extractor))?;
+        extractor.extract(source, file.path.strip_prefix(root).unwrap_or(&file.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{ExtractorRegistry, LanguageExtractor};
+    use crate::parser::{ExtractedExports, ExtractedFunction};
+    use crate::walker::{SourceFile, WalkOptions};
+
+    struct ShellExtractor;
+
+    impl LanguageExtractor for ShellExtractor {
+        fn name(&self) -> &str {
+            "shell"
+        }
+
+        fn handles(&self, path: &Path, _options: &WalkOptions) -> bool {
+            path.extension()
+                .is_some_and(|ext| ext == "sh" || ext == "ts")
+        }
+
+        fn extract(&mut self, source: &str, _path: &Path) -> Result<ExtractedExports, String> {
+            let functions = source
+                .lines()
+                .enumerate()
+                .filter_map(|(idx, line)| {
+                    let name = line.strip_suffix("() {")?;
+                    Some(ExtractedFunction {
+                        name: name.to_string(),
+                        signature: format!("{name}()"),
+                        line: idx + 1,
+                        ..ExtractedFunction::default()
+                    })
+                })
+                .collect();
+            Ok(ExtractedExports {
+                functions,
+                ..ExtractedExports::default()
+            })
+        }
+    }
+
+    #[test]
+    fn later_registrations_take_precedence() {
+        let mut registry = ExtractorRegistry::builtin().expect("registry");
+        let options = WalkOptions::default();
+        let typescript = registry.classify(Path::new("a.ts"), &options);
+        assert_eq!(typescript, Some(0));
+        assert_eq!(registry.classify(Path::new("a.sh"), &options), None);
+
+        registry.register(ShellExtractor);
+        let shell = registry
+            .classify(Path::new("a.sh"), &options)
+            .expect("shell");
+        assert_eq!(registry.names()[shell], "shell");
+        assert_eq!(registry.classify(Path::new("a.ts"), &options), Some(shell));
+
+        let file = SourceFile {
+            path: "deploy.sh".into(),
+            extractor: shell,
+        };
+        let exports = registry
+            .extract(&file, Path::new(""), "#!/bin/sh\ndeploy() {\n}\n")
+            .expect("extract");
+        assert_eq!(exports.functions[0].name, "deploy");
+        assert_eq!(exports.functions[0].line, 2);
+    }
+}
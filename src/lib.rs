@@ -1,66 +1,326 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use serde::Serialize;
+
+pub mod budget;
+pub mod docs;
+#[cfg(feature = "go")]
+pub mod go;
+mod importance;
+pub mod inject;
+pub mod json;
+pub mod language;
 pub mod markdown;
 pub mod parser;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod queries;
+#[cfg(feature = "rust")]
+pub mod rust;
+pub mod split;
+#[cfg(feature = "templates")]
+pub mod template;
 pub mod walker;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::docs::DocComment;
+use crate::language::ExtractorRegistry;
+
+/// Syntax errors listed per file; the rest are only counted.
+pub const MAX_DIAGNOSTICS_PER_FILE: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub enum RenderProfile {
     Compact,
+    #[default]
     Balanced,
     Detailed,
 }
 
-impl Default for RenderProfile {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+/// Order of the per-file sections in Markdown output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    #[default]
+    Path,
+    /// Highest [`FileResult::importance`] first, ties by path; exports within
+    /// a file by their own score, ties by line.
+    Importance,
+}
+
+/// How Markdown lays out the exports inside a section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Grouping {
+    /// A `### path` heading per file, or per package for Go.
+    #[default]
+    File,
+    /// A heading per directory, with its files as bullets holding their exports.
+    Directory,
+    /// No headings: every export alphabetically by name, suffixed with its path.
+    Symbol,
+}
+
+/// A top-level part of the Markdown output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    /// The [`RunSummary`] counts.
+    Summary,
+    Tree,
+    Functions,
+    Types,
+    Props,
+    /// User query sections.
+    Queries,
+    /// Only rendered with `hide_deprecated`.
+    Deprecated,
+    /// Parse and walk errors.
+    Errors,
+}
+
+impl Section {
+    pub const ALL: [Section; 8] = [
+        Section::Summary,
+        Section::Tree,
+        Section::Functions,
+        Section::Types,
+        Section::Props,
+        Section::Queries,
+        Section::Deprecated,
+        Section::Errors,
+    ];
+}
+
+/// Which [`Section`]s are rendered, in order. Fixed-size so [`RenderConfig`]
+/// stays `Copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sections {
+    order: [Option<Section>; Section::ALL.len()],
+}
+
+impl Sections {
+    /// `sections` in the given order; repeats after the first are ignored.
+    pub fn new(sections: &[Section]) -> Self {
+        let mut order = [None; Section::ALL.len()];
+        let mut len = 0;
+        for &section in sections {
+            if !order.contains(&Some(section)) {
+                order[len] = Some(section);
+                len += 1;
+            }
+        }
+        Self { order }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Section> + '_ {
+        self.order.iter().map_while(|section| *section)
+    }
+
+    pub fn contains(&self, section: Section) -> bool {
+        self.order.contains(&Some(section))
+    }
+}
+
+/// Everything but the summary, in the classic layout.
+impl Default for Sections {
     fn default() -> Self {
-        Self::Balanced
+        Self::new(&[
+            Section::Tree,
+            Section::Functions,
+            Section::Types,
+            Section::Props,
+            Section::Queries,
+            Section::Deprecated,
+            Section::Errors,
+        ])
     }
 }
 
+/// How Markdown output is divided across files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SplitMode {
+    /// Everything in the one output file.
+    #[default]
+    Single,
+    /// A `CONTEXT.md` per top-level directory, indexed from the output file.
+    Directory,
+    /// A `CONTEXT.md` per package (a directory with `package.json`,
+    /// `Cargo.toml`, `go.mod`, `pyproject.toml` or `setup.py`).
+    Package,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RenderConfig {
     pub profile: RenderProfile,
+    pub format: OutputFormat,
     pub include_types: bool,
     pub tree_depth: usize,
+    pub hide_internal: bool,
+    pub hide_deprecated: bool,
+    /// Estimated token budget for Markdown output; past it the map degrades
+    /// until it fits and notes what was elided.
+    pub max_tokens: Option<usize>,
+    pub sort: SortOrder,
+    pub grouping: Grouping,
+    /// Draw tree directories without scanned source files as `dir/ (N files)`.
+    pub collapse_non_source_dirs: bool,
+    /// Entries listed per tree directory; the rest collapse into
+    /// `… N more (.ext)` lines.
+    pub tree_max_children: Option<usize>,
+    /// Leave files that were not scanned, and directories without any, out of
+    /// the tree.
+    pub tree_source_only: bool,
+    /// Markdown only; JSON is always one file.
+    pub split: SplitMode,
+    /// Markdown sections to render, in order. `include_types` and
+    /// `hide_deprecated` still gate their sections.
+    pub sections: Sections,
 }
 
 impl Default for RenderConfig {
     fn default() -> Self {
         Self {
             profile: RenderProfile::Balanced,
+            format: OutputFormat::Markdown,
             include_types: true,
             tree_depth: 10,
+            hide_internal: false,
+            hide_deprecated: false,
+            max_tokens: None,
+            sort: SortOrder::Path,
+            grouping: Grouping::File,
+            collapse_non_source_dirs: false,
+            tree_max_children: None,
+            tree_source_only: false,
+            split: SplitMode::Single,
+            sections: Sections::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Which files are scanned and how failures are treated; rendering is
+/// configured separately by [`RenderConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScanConfig {
+    /// Also scan `.d.ts` files for ambient declarations.
+    pub include_declarations: bool,
+    /// Fail the run on unreadable entries or parse failures instead of
+    /// reporting them in the output.
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct FunctionExport {
     pub name: String,
     pub signature: String,
     pub file_path: String,
     pub line: usize,
+    pub doc: Option<DocComment>,
+    pub deprecated: Option<String>,
+    pub overloads: Vec<String>,
+    pub ambient: bool,
+    pub namespace: Option<String>,
+    /// The file's score plus points for this export's documentation.
+    pub importance: u32,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct TypeExport {
     pub name: String,
+    /// Declaration header, when it says more than the name (Python class
+    /// decorators and bases).
+    pub signature: Option<String>,
     pub file_path: String,
     pub line: usize,
+    pub doc: Option<DocComment>,
+    pub deprecated: Option<String>,
+    pub ambient: bool,
+    pub namespace: Option<String>,
+    /// The file's score plus points for this export's documentation.
+    pub importance: u32,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// A prop declared by a component (Svelte `export let` or `$props()`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentProp {
+    pub name: String,
+    pub type_annotation: Option<String>,
+    pub default_value: Option<String>,
+    pub file_path: String,
+    pub line: usize,
+}
+
+/// A match of a user query from `.context-map/queries/`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CustomExport {
+    /// Title of the section the match is listed under.
+    pub section: String,
+    pub name: String,
+    pub signature: Option<String>,
+    pub kind: Option<String>,
+    pub file_path: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct FileResult {
     pub file_path: String,
     pub function_exports: Vec<FunctionExport>,
     pub type_exports: Vec<TypeExport>,
-    pub parse_error: Option<String>,
+    pub component_props: Vec<ComponentProp>,
+    pub custom_exports: Vec<CustomExport>,
+    /// Package the file belongs to, for languages that group by package (Go).
+    pub package: Option<String>,
+    pub parse_error: Option<ParseError>,
+    /// Ranking score from import fan-in, barrel re-exports, recent git
+    /// changes and documentation; higher is more central.
+    pub importance: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParseError {
+    pub message: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Syntax errors beyond `MAX_DIAGNOSTICS_PER_FILE`, counted but not listed.
+    pub omitted: usize,
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            diagnostics: Vec::new(),
+            omitted: 0,
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// One `ERROR`/`MISSING` node; `line` and `column` are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub kind: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
 pub struct RunSummary {
     pub scanned: usize,
     pub parsed: usize,
@@ -69,26 +329,64 @@ pub struct RunSummary {
     pub exported_types: usize,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct RunOutput {
     pub root_path: String,
     pub repo_entries: Vec<RepoEntry>,
     pub summary: RunSummary,
     pub file_results: Vec<FileResult>,
+    pub walk_errors: Vec<WalkError>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct RepoEntry {
     pub path: String,
     pub is_dir: bool,
     pub depth: usize,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WalkError {
+    pub path: String,
+    pub message: String,
+}
+
 #[derive(Debug)]
 pub enum ContextMapError {
     InvalidRoot(PathBuf),
     ParserInit(String),
-    Io(std::io::Error),
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A directory entry the walker could not read; only fatal with `strict`.
+    Walk {
+        path: PathBuf,
+        message: String,
+    },
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A source file that failed to parse; only fatal with `strict`.
+    Parse {
+        path: PathBuf,
+        message: String,
+    },
+    /// The `--inject` target has markers that do not form one block.
+    Inject {
+        path: PathBuf,
+        message: String,
+    },
+    /// A `--template` that fails to parse or render.
+    Template {
+        path: PathBuf,
+        message: String,
+    },
 }
 
 impl Display for ContextMapError {
@@ -96,16 +394,37 @@ impl Display for ContextMapError {
         match self {
             Self::InvalidRoot(path) => write!(f, "invalid root path: {}", path.display()),
             Self::ParserInit(msg) => write!(f, "failed to initialize parser: {msg}"),
-            Self::Io(err) => write!(f, "io error: {err}"),
+            Self::Io { path, source } => write!(f, "io error at {}: {source}", path.display()),
+            Self::Walk { path, message } => {
+                write!(f, "failed to walk {}: {message}", path.display())
+            }
+            Self::Read { path, source } => {
+                write!(f, "failed to read {}: {source}", path.display())
+            }
+            Self::Write { path, source } => {
+                write!(f, "failed to write {}: {source}", path.display())
+            }
+            Self::Parse { path, message } => {
+                write!(f, "failed to parse {}: {message}", path.display())
+            }
+            Self::Inject { path, message } => {
+                write!(f, "cannot inject into {}: {message}", path.display())
+            }
+            Self::Template { path, message } => {
+                write!(f, "failed to render template {}: {message}", path.display())
+            }
         }
     }
 }
 
-impl Error for ContextMapError {}
-
-impl From<std::io::Error> for ContextMapError {
-    fn from(value: std::io::Error) -> Self {
-        Self::Io(value)
+impl Error for ContextMapError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } | Self::Read { source, .. } | Self::Write { source, .. } => {
+                Some(source)
+            }
+            _ => None,
+        }
     }
 }
 
@@ -117,13 +436,55 @@ pub fn generate_context_map_with_depth(
     root: &Path,
     tree_depth: usize,
 ) -> Result<RunOutput, ContextMapError> {
+    generate_context_map_with_config(
+        root,
+        ScanConfig::default(),
+        RenderConfig {
+            tree_depth,
+            ..RenderConfig::default()
+        },
+    )
+}
+
+pub fn generate_context_map_with_config(
+    root: &Path,
+    scan: ScanConfig,
+    config: RenderConfig,
+) -> Result<RunOutput, ContextMapError> {
+    let user_queries = queries::load_user_queries(root).map_err(|source| ContextMapError::Io {
+        path: root.join(queries::QUERY_DIR),
+        source,
+    })?;
+    let mut registry =
+        ExtractorRegistry::builtin_with_queries(&user_queries).map_err(ContextMapError::ParserInit)?;
+    generate_context_map_with_registry(root, scan, config, &mut registry)
+}
+
+/// Like [`generate_context_map_with_config`], with a caller-supplied set of
+/// language extractors.
+pub fn generate_context_map_with_registry(
+    root: &Path,
+    scan: ScanConfig,
+    config: RenderConfig,
+    registry: &mut ExtractorRegistry,
+) -> Result<RunOutput, ContextMapError> {
+    let tree_depth = config.tree_depth;
     if !root.is_dir() {
         return Err(ContextMapError::InvalidRoot(root.to_path_buf()));
     }
 
-    let canonical_root = fs::canonicalize(root)?;
-    let mut ts_parser = parser::TsExportParser::new().map_err(ContextMapError::ParserInit)?;
-    let repo_entries = walker::collect_repo_entries(&canonical_root, tree_depth)?
+    let canonical_root = fs::canonicalize(root).map_err(|source| ContextMapError::Io {
+        path: root.to_path_buf(),
+        source,
+    })?;
+    let root_io_error = |source| ContextMapError::Io {
+        path: canonical_root.clone(),
+        source,
+    };
+    let repo_walk =
+        walker::collect_repo_entries(&canonical_root, tree_depth).map_err(root_io_error)?;
+    let repo_entries = repo_walk
+        .entries
         .into_iter()
         .map(|entry| RepoEntry {
             path: normalize_path(entry.path.strip_prefix(&canonical_root).unwrap_or(&entry.path)),
@@ -131,7 +492,32 @@ pub fn generate_context_map_with_depth(
             depth: entry.depth,
         })
         .collect::<Vec<_>>();
-    let files = walker::collect_source_files(&canonical_root)?;
+    let walk_options = walker::WalkOptions {
+        include_declarations: scan.include_declarations,
+    };
+    let walk = walker::collect_source_files_with_registry(&canonical_root, &walk_options, registry)
+        .map_err(root_io_error)?;
+    // Both walks see the same unreadable directories; report each once.
+    let mut raw_walk_errors = repo_walk.errors;
+    for err in walk.errors {
+        if !raw_walk_errors.contains(&err) {
+            raw_walk_errors.push(err);
+        }
+    }
+    if let Some(err) = raw_walk_errors.first().filter(|_| scan.strict) {
+        return Err(ContextMapError::Walk {
+            path: err.path.clone(),
+            message: err.message.clone(),
+        });
+    }
+    let walk_errors = raw_walk_errors
+        .into_iter()
+        .map(|err| WalkError {
+            path: normalize_path(err.path.strip_prefix(&canonical_root).unwrap_or(&err.path)),
+            message: err.message,
+        })
+        .collect::<Vec<_>>();
+    let files = walk.files;
 
     let mut summary = RunSummary {
         scanned: files.len(),
@@ -139,6 +525,7 @@ pub fn generate_context_map_with_depth(
     };
 
     let mut file_results: Vec<FileResult> = Vec::with_capacity(files.len());
+    let mut imports = HashMap::new();
 
     for source_file in files {
         let relative = normalize_path(
@@ -149,9 +536,45 @@ pub fn generate_context_map_with_depth(
         );
 
         match fs::read_to_string(&source_file.path) {
-            Ok(source) => match ts_parser.extract_exports_for_source(&source, &source_file.kind) {
+            Ok(source) => match registry.extract(&source_file, &canonical_root, &source) {
                 Ok(extracted) => {
-                    summary.parsed += 1;
+                    // Files with syntax errors keep their intact exports but are
+                    // still reported, so they count as failed rather than parsed.
+                    let parse_error = if extracted.syntax_errors.is_empty() {
+                        summary.parsed += 1;
+                        None
+                    } else {
+                        if scan.strict {
+                            let first = &extracted.syntax_errors[0];
+                            return Err(ContextMapError::Parse {
+                                path: source_file.path,
+                                message: format!(
+                                    "syntax parse error at {}:{}",
+                                    first.line, first.column
+                                ),
+                            });
+                        }
+                        summary.parse_failed += 1;
+                        let total = extracted.syntax_errors.len();
+                        let diagnostics = extracted
+                            .syntax_errors
+                            .into_iter()
+                            .take(MAX_DIAGNOSTICS_PER_FILE)
+                            .map(|err| Diagnostic {
+                                line: err.line,
+                                column: err.column,
+                                start_byte: err.start_byte,
+                                end_byte: err.end_byte,
+                                kind: err.kind,
+                                snippet: err.snippet,
+                            })
+                            .collect::<Vec<_>>();
+                        Some(ParseError {
+                            message: "syntax parse error".to_string(),
+                            omitted: total - diagnostics.len(),
+                            diagnostics,
+                        })
+                    };
                     summary.exported_functions += extracted.functions.len();
                     summary.exported_types += extracted.types.len();
                     let function_exports = extracted
@@ -162,6 +585,12 @@ pub fn generate_context_map_with_depth(
                             signature: entry.signature,
                             file_path: relative.clone(),
                             line: entry.line,
+                            doc: entry.doc,
+                            deprecated: entry.deprecated,
+                            overloads: entry.overloads,
+                            ambient: entry.ambient,
+                            namespace: entry.namespace,
+                            importance: 0,
                         })
                         .collect::<Vec<_>>();
                     let type_exports = extracted
@@ -169,35 +598,89 @@ pub fn generate_context_map_with_depth(
                         .into_iter()
                         .map(|entry| TypeExport {
                             name: entry.name,
+                            signature: entry.signature,
+                            file_path: relative.clone(),
+                            line: entry.line,
+                            doc: entry.doc,
+                            deprecated: entry.deprecated,
+                            ambient: entry.ambient,
+                            namespace: entry.namespace,
+                            importance: 0,
+                        })
+                        .collect::<Vec<_>>();
+                    let component_props = extracted
+                        .props
+                        .into_iter()
+                        .map(|entry| ComponentProp {
+                            name: entry.name,
+                            type_annotation: entry.type_annotation,
+                            default_value: entry.default_value,
+                            file_path: relative.clone(),
+                            line: entry.line,
+                        })
+                        .collect::<Vec<_>>();
+                    let custom_exports = extracted
+                        .custom
+                        .into_iter()
+                        .map(|entry| CustomExport {
+                            section: entry.section,
+                            name: entry.name,
+                            signature: entry.signature,
+                            kind: entry.kind,
                             file_path: relative.clone(),
                             line: entry.line,
                         })
                         .collect::<Vec<_>>();
 
+                    imports.insert(relative.clone(), extracted.imports);
                     file_results.push(FileResult {
                         file_path: relative,
                         function_exports,
                         type_exports,
-                        parse_error: None,
+                        component_props,
+                        custom_exports,
+                        package: extracted.package,
+                        parse_error,
+                        importance: 0,
                     });
                 }
                 Err(err) => {
+                    if scan.strict {
+                        return Err(ContextMapError::Parse {
+                            path: source_file.path,
+                            message: err,
+                        });
+                    }
                     summary.parse_failed += 1;
                     file_results.push(FileResult {
                         file_path: relative,
                         function_exports: Vec::new(),
                         type_exports: Vec::new(),
-                        parse_error: Some(err),
+                        component_props: Vec::new(),
+                        custom_exports: Vec::new(),
+                        package: None,
+                        parse_error: Some(ParseError::new(err)),
+                        importance: 0,
                     });
                 }
             },
             Err(err) => {
+                if scan.strict {
+                    return Err(ContextMapError::Read {
+                        path: source_file.path,
+                        source: err,
+                    });
+                }
                 summary.parse_failed += 1;
                 file_results.push(FileResult {
                     file_path: relative,
                     function_exports: Vec::new(),
                     type_exports: Vec::new(),
-                    parse_error: Some(err.to_string()),
+                    component_props: Vec::new(),
+                    custom_exports: Vec::new(),
+                    package: None,
+                    parse_error: Some(ParseError::new(err.to_string())),
+                    importance: 0,
                 });
             }
         }
@@ -210,27 +693,127 @@ pub fn generate_context_map_with_depth(
         file.type_exports
             .sort_by(|a, b| a.line.cmp(&b.line).then(a.name.cmp(&b.name)));
     }
+    // Ranking reads git history, so scores are only computed when the output
+    // uses them and the default Markdown map stays stable across commits.
+    if config.sort == SortOrder::Importance
+        || config.max_tokens.is_some()
+        || config.format == OutputFormat::Json
+    {
+        importance::rank(&canonical_root, &mut file_results, &imports);
+    }
 
     Ok(RunOutput {
         root_path: canonical_root.display().to_string(),
         repo_entries,
         summary,
         file_results,
+        walk_errors,
     })
 }
 
 pub fn run(root: &Path, out: &Path) -> Result<RunSummary, ContextMapError> {
-    run_with_config(root, out, RenderConfig::default())
+    run_with_config(root, out, ScanConfig::default(), RenderConfig::default())
 }
 
 pub fn run_with_config(
     root: &Path,
     out: &Path,
+    scan: ScanConfig,
     config: RenderConfig,
 ) -> Result<RunSummary, ContextMapError> {
-    let output = generate_context_map_with_depth(root, config.tree_depth)?;
-    let markdown = markdown::render_markdown_with_config(&output, config);
-    fs::write(out, markdown)?;
+    let output = generate_context_map_with_config(root, scan, config)?;
+    let rendered = match config.format {
+        OutputFormat::Markdown if config.split != SplitMode::Single => {
+            split::write_split(out, &output, config)?;
+            return Ok(output.summary);
+        }
+        OutputFormat::Markdown => {
+            split::remove_previous_parts(out, Path::new(&output.root_path))?;
+            markdown::render_markdown_with_config(&output, config)
+        }
+        OutputFormat::Json => json::render_json(&output),
+    };
+    fs::write(out, rendered).map_err(|source| ContextMapError::Write {
+        path: out.to_path_buf(),
+        source,
+    })?;
+    Ok(output.summary)
+}
+
+/// Renders the map through the user template at `template` (see [`template`])
+/// instead of the built-in Markdown layout. Templates render a single Markdown
+/// document without a token budget, so other formats, `max_tokens` and split
+/// output are rejected rather than ignored.
+#[cfg(feature = "templates")]
+pub fn run_with_template(
+    root: &Path,
+    out: &Path,
+    scan: ScanConfig,
+    config: RenderConfig,
+    template: &Path,
+) -> Result<RunSummary, ContextMapError> {
+    let unsupported = if config.format != OutputFormat::Markdown {
+        Some("templates only render Markdown output")
+    } else if config.max_tokens.is_some() {
+        Some("templates do not support a token budget")
+    } else if config.split != SplitMode::Single {
+        Some("templates do not support split output")
+    } else {
+        None
+    };
+    if let Some(message) = unsupported {
+        return Err(ContextMapError::Template {
+            path: template.to_path_buf(),
+            message: message.to_string(),
+        });
+    }
+    let source = fs::read_to_string(template).map_err(|source| ContextMapError::Read {
+        path: template.to_path_buf(),
+        source,
+    })?;
+    let output = generate_context_map_with_config(root, scan, config)?;
+    let rendered = template::render_template(&output, config, &source).map_err(|message| {
+        ContextMapError::Template {
+            path: template.to_path_buf(),
+            message,
+        }
+    })?;
+    fs::write(out, rendered).map_err(|source| ContextMapError::Write {
+        path: out.to_path_buf(),
+        source,
+    })?;
+    Ok(output.summary)
+}
+
+/// Renders Markdown into the managed block of `target` (see [`inject`]),
+/// creating the file or the block when missing.
+pub fn inject_with_config(
+    root: &Path,
+    target: &Path,
+    scan: ScanConfig,
+    config: RenderConfig,
+) -> Result<RunSummary, ContextMapError> {
+    let document = match fs::read_to_string(target) {
+        Ok(document) => document,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(source) => {
+            return Err(ContextMapError::Read {
+                path: target.to_path_buf(),
+                source,
+            });
+        }
+    };
+    let output = generate_context_map_with_config(root, scan, config)?;
+    let block = markdown::render_markdown_with_config(&output, config);
+    let injected =
+        inject::inject_block(&document, &block).map_err(|message| ContextMapError::Inject {
+            path: target.to_path_buf(),
+            message,
+        })?;
+    fs::write(target, injected).map_err(|source| ContextMapError::Write {
+        path: target.to_path_buf(),
+        source,
+    })?;
     Ok(output.summary)
 }
 
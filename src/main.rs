@@ -2,7 +2,10 @@ use std::path::PathBuf;
 
 use clap::{Parser, ValueEnum};
 
-use context_map::{RenderConfig, RenderProfile};
+use context_map::{
+    Grouping, OutputFormat, RenderConfig, RenderProfile, ScanConfig, Section, Sections, SortOrder,
+    SplitMode,
+};
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum ProfileArg {
@@ -21,9 +24,100 @@ impl From<ProfileArg> for RenderProfile {
     }
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FormatArg {
+    Markdown,
+    Json,
+}
+
+impl From<FormatArg> for OutputFormat {
+    fn from(value: FormatArg) -> Self {
+        match value {
+            FormatArg::Markdown => OutputFormat::Markdown,
+            FormatArg::Json => OutputFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SortArg {
+    Path,
+    Importance,
+}
+
+impl From<SortArg> for SortOrder {
+    fn from(value: SortArg) -> Self {
+        match value {
+            SortArg::Path => SortOrder::Path,
+            SortArg::Importance => SortOrder::Importance,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GroupArg {
+    File,
+    Directory,
+    Symbol,
+}
+
+impl From<GroupArg> for Grouping {
+    fn from(value: GroupArg) -> Self {
+        match value {
+            GroupArg::File => Grouping::File,
+            GroupArg::Directory => Grouping::Directory,
+            GroupArg::Symbol => Grouping::Symbol,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SplitArg {
+    None,
+    Directory,
+    Package,
+}
+
+impl From<SplitArg> for SplitMode {
+    fn from(value: SplitArg) -> Self {
+        match value {
+            SplitArg::None => SplitMode::Single,
+            SplitArg::Directory => SplitMode::Directory,
+            SplitArg::Package => SplitMode::Package,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SectionArg {
+    Summary,
+    Tree,
+    Functions,
+    Types,
+    Props,
+    Queries,
+    Deprecated,
+    Errors,
+}
+
+impl From<SectionArg> for Section {
+    fn from(value: SectionArg) -> Self {
+        match value {
+            SectionArg::Summary => Section::Summary,
+            SectionArg::Tree => Section::Tree,
+            SectionArg::Functions => Section::Functions,
+            SectionArg::Types => Section::Types,
+            SectionArg::Props => Section::Props,
+            SectionArg::Queries => Section::Queries,
+            SectionArg::Deprecated => Section::Deprecated,
+            SectionArg::Errors => Section::Errors,
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 #[command(name = "context-map")]
-#[command(about = "Scan TS/TSX/Vue exports and write a Markdown context map")]
+#[command(about = "Scan TS/JS, component, Python, Rust and Go exports and write a Markdown context map")]
 struct Args {
     #[arg(long, default_value = ".")]
     root: PathBuf,
@@ -34,24 +128,115 @@ struct Args {
     #[arg(long, value_enum, default_value_t = ProfileArg::Balanced)]
     profile: ProfileArg,
 
+    #[arg(long, value_enum, default_value_t = FormatArg::Markdown)]
+    format: FormatArg,
+
     #[arg(long, default_value_t = false)]
     no_types: bool,
 
     #[arg(long, default_value_t = 10)]
     tree_depth: usize,
+
+    #[arg(long, default_value_t = false)]
+    hide_internal: bool,
+
+    #[arg(long, default_value_t = false)]
+    hide_deprecated: bool,
+
+    #[arg(long, default_value_t = false)]
+    include_declarations: bool,
+
+    #[arg(long, default_value_t = false)]
+    strict: bool,
+
+    #[arg(long)]
+    max_tokens: Option<usize>,
+
+    #[arg(long, value_enum, default_value_t = SortArg::Path)]
+    sort: SortArg,
+
+    #[arg(long, value_enum, default_value_t = GroupArg::File)]
+    group_by: GroupArg,
+
+    #[arg(long, default_value_t = false)]
+    collapse_non_source_dirs: bool,
+
+    #[arg(long)]
+    tree_max_children: Option<usize>,
+
+    #[arg(long, default_value_t = false)]
+    tree_source_only: bool,
+
+    #[arg(long, value_enum, default_value_t = SplitArg::None)]
+    split: SplitArg,
+
+    #[arg(long, conflicts_with_all = ["out", "format", "split"])]
+    inject: Option<PathBuf>,
+
+    #[arg(long, value_enum, value_delimiter = ',')]
+    sections: Vec<SectionArg>,
+
+    #[cfg(feature = "templates")]
+    #[arg(long, conflicts_with_all = ["format", "split", "inject", "max_tokens"])]
+    template: Option<PathBuf>,
+
+    #[cfg(feature = "templates")]
+    #[arg(long, exclusive = true)]
+    print_default_template: bool,
 }
 
 fn main() {
     let args = Args::parse();
-    let output = args.out.unwrap_or_else(|| args.root.join("REPO.md"));
+    #[cfg(feature = "templates")]
+    if args.print_default_template {
+        print!("{}", context_map::template::DEFAULT_TEMPLATE);
+        return;
+    }
+    let format: OutputFormat = args.format.into();
+    let default_name = match format {
+        OutputFormat::Markdown => "REPO.md",
+        OutputFormat::Json => "REPO.json",
+    };
+    let output = args.out.unwrap_or_else(|| args.root.join(default_name));
     let profile: RenderProfile = args.profile.into();
+    let scan = ScanConfig {
+        include_declarations: args.include_declarations,
+        strict: args.strict,
+    };
     let config = RenderConfig {
         profile,
+        format,
         include_types: !args.no_types,
         tree_depth: args.tree_depth,
+        hide_internal: args.hide_internal,
+        hide_deprecated: args.hide_deprecated,
+        max_tokens: args.max_tokens,
+        sort: args.sort.into(),
+        grouping: args.group_by.into(),
+        collapse_non_source_dirs: args.collapse_non_source_dirs,
+        tree_max_children: args.tree_max_children,
+        tree_source_only: args.tree_source_only,
+        split: args.split.into(),
+        sections: if args.sections.is_empty() {
+            Sections::default()
+        } else {
+            let sections = args.sections.iter().map(|&section| section.into()).collect::<Vec<_>>();
+            Sections::new(&sections)
+        },
+    };
+
+    let result = match &args.inject {
+        Some(target) => context_map::inject_with_config(&args.root, target, scan, config),
+        #[cfg(feature = "templates")]
+        None if args.template.is_some() => {
+            let template = args.template.as_deref().expect("checked above");
+            context_map::run_with_template(&args.root, &output, scan, config, template)
+        }
+        None => context_map::run_with_config(&args.root, &output, scan, config),
     };
+    let output = args.inject.unwrap_or(output);
 
-    match context_map::run_with_config(&args.root, &output, config) {
+    match result {
         Ok(summary) => {
             println!(
                 "Profile={:?}, types={}, tree_depth={} -> wrote {} exported functions and {} exported types from {} scanned files to {}",
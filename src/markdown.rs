@@ -1,11 +1,26 @@
-use std::collections::BTreeMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::{RenderConfig, RenderProfile, RepoEntry, RunOutput};
+use serde::Serialize;
+
+use crate::budget;
+use crate::docs::DocComment;
+use crate::{
+    FileResult, Grouping, RenderConfig, RenderProfile, RepoEntry, RunOutput, RunSummary, Section,
+    SortOrder,
+};
 
 #[derive(Default)]
 struct TreeNode {
     is_dir: bool,
     children: BTreeMap<String, TreeNode>,
+    /// Files listed beneath a directory.
+    files: usize,
+    /// Scanned source files at or beneath this node, and what they export.
+    sources: usize,
+    functions: usize,
+    types: usize,
+    parse_errors: usize,
 }
 
 pub fn render_markdown(output: &RunOutput) -> String {
@@ -13,78 +28,924 @@ pub fn render_markdown(output: &RunOutput) -> String {
 }
 
 pub fn render_markdown_with_config(output: &RunOutput, config: RenderConfig) -> String {
+    match config.max_tokens {
+        Some(max_tokens) => budget::render_within_budget(output, config, max_tokens),
+        None => render_with_plan(output, config, &RenderPlan::default()),
+    }
+}
+
+/// What the token budget cut from a render. The default plan elides nothing.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RenderPlan {
+    /// Deepest repository tree level shown.
+    pub(crate) tree_depth: Option<usize>,
+    /// Files listed per tree directory before the rest collapse into a count.
+    pub(crate) tree_max_files: Option<usize>,
+    /// Per-file profile overrides, keyed by file path.
+    pub(crate) profiles: HashMap<String, RenderProfile>,
+    /// Directories whose files are only counted.
+    pub(crate) summarized: Vec<String>,
+    /// The budget, when rendering under one; enables the trailing notes.
+    pub(crate) budget: Option<usize>,
+    pub(crate) over_budget: bool,
+}
+
+impl RenderPlan {
+    pub(crate) fn profile_for(&self, file_path: &str, default: RenderProfile) -> RenderProfile {
+        self.profiles.get(file_path).copied().unwrap_or(default)
+    }
+
+    pub(crate) fn is_summarized(&self, file_path: &str) -> bool {
+        self.summarized.iter().any(|dir| is_within(file_path, dir))
+    }
+}
+
+pub(crate) fn is_within(file_path: &str, dir: &str) -> bool {
+    file_path
+        .strip_prefix(dir)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+pub(crate) fn render_with_plan(
+    output: &RunOutput,
+    config: RenderConfig,
+    plan: &RenderPlan,
+) -> String {
+    let visible_files = visible_files(output, config, plan);
+
     let mut lines = Vec::new();
+    for section in config.sections.iter() {
+        lines.extend(render_section(section, output, &visible_files, config, plan));
+    }
+    lines.extend(render_summarized_section(output, plan));
+    lines.extend(render_budget_notes(plan, &Trimmed::of(output, plan)));
 
-    lines.push("# Repository Structure".to_string());
-    lines.push("```text".to_string());
-    lines.extend(render_repo_tree(&output.repo_entries));
-    lines.push("```".to_string());
-    lines.push(String::new());
+    let start = lines.iter().take_while(|line| line.is_empty()).count();
+    lines[start..].join("\n") + "\n"
+}
+
+/// One built-in section as Markdown, without the blank line that separates it
+/// from the previous one; empty when the section has nothing to show.
+#[cfg(feature = "templates")]
+pub(crate) fn render_section_text(
+    output: &RunOutput,
+    config: RenderConfig,
+    section: Section,
+) -> String {
+    let plan = RenderPlan::default();
+    let visible_files = visible_files(output, config, &plan);
+    let lines = render_section(section, output, &visible_files, config, &plan);
+    let start = lines.iter().take_while(|line| line.is_empty()).count();
+    lines[start..].join("\n")
+}
+
+/// The repository tree lines, without the heading and code fence.
+#[cfg(feature = "templates")]
+pub(crate) fn render_tree_text(output: &RunOutput, config: RenderConfig) -> String {
+    tree_lines(output, config, &RenderPlan::default()).join("\n")
+}
 
-    lines.push("# Exported Functions".to_string());
+/// A section's visible exports laid out for `config.grouping`; `title` picks
+/// the user query section. Empty for sections that do not list exports.
+#[cfg(feature = "templates")]
+pub(crate) fn section_groups(
+    output: &RunOutput,
+    config: RenderConfig,
+    section: Section,
+    title: Option<&str>,
+) -> Vec<Group> {
+    let files = visible_files(output, config, &RenderPlan::default())
+        .into_iter()
+        .map(|file| {
+            let entries = match section {
+                Section::Functions => function_entries(file, config, config.profile),
+                Section::Types => type_entries(file, config, config.profile),
+                Section::Props => prop_entries(file, config.profile),
+                Section::Queries => custom_entries(file, title.unwrap_or_default(), config.profile),
+                Section::Deprecated => deprecated_entries(file, config, config.profile),
+                Section::Summary | Section::Tree | Section::Errors => Vec::new(),
+            };
+            (file, entries)
+        })
+        .filter(|(_, entries)| !entries.is_empty())
+        .collect();
+    group_entries(files, config.grouping)
+}
+
+/// User query section titles, in the order the Markdown lists them.
+#[cfg(feature = "templates")]
+pub(crate) fn section_query_titles(output: &RunOutput, config: RenderConfig) -> Vec<String> {
+    query_titles(visible_files(output, config, &RenderPlan::default()))
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
 
-    let files_with_exports = output
+fn visible_files<'a>(
+    output: &'a RunOutput,
+    config: RenderConfig,
+    plan: &RenderPlan,
+) -> Vec<&'a FileResult> {
+    let mut visible_files = output
         .file_results
         .iter()
-        .filter(|f| !f.function_exports.is_empty())
+        .filter(|f| !plan.is_summarized(&f.file_path))
+        .collect::<Vec<_>>();
+    if config.sort == SortOrder::Importance {
+        visible_files.sort_by_key(|f| Reverse(f.importance));
+    }
+    visible_files
+}
+
+fn render_section(
+    section: Section,
+    output: &RunOutput,
+    visible_files: &[&FileResult],
+    config: RenderConfig,
+    plan: &RenderPlan,
+) -> Vec<String> {
+    match section {
+        Section::Summary => render_summary_section(&output.summary),
+        Section::Tree => render_tree_section(output, config, plan),
+        Section::Functions => render_functions_section(visible_files, config, plan),
+        Section::Types if config.include_types => {
+            render_types_section(visible_files, config, plan)
+        }
+        Section::Props => render_props_section(visible_files, config, plan),
+        Section::Queries => render_custom_sections(visible_files, config, plan),
+        Section::Deprecated if config.hide_deprecated => {
+            render_deprecated_section(visible_files, config, plan)
+        }
+        Section::Types | Section::Deprecated => Vec::new(),
+        Section::Errors => render_error_sections(output),
+    }
+}
+
+fn render_summary_section(summary: &RunSummary) -> Vec<String> {
+    vec![
+        String::new(),
+        "# Summary".to_string(),
+        format!("- Scanned files: {}", summary.scanned),
+        format!("- Parsed: {}", summary.parsed),
+        format!("- Parse failures: {}", summary.parse_failed),
+        format!("- Exported functions: {}", summary.exported_functions),
+        format!("- Exported types: {}", summary.exported_types),
+    ]
+}
+
+pub(crate) fn render_tree_section(output: &RunOutput, config: RenderConfig, plan: &RenderPlan) -> Vec<String> {
+    let mut lines = vec![
+        String::new(),
+        "# Repository Structure".to_string(),
+        "```text".to_string(),
+    ];
+    lines.extend(tree_lines(output, config, plan));
+    lines.push("```".to_string());
+    lines
+}
+
+fn tree_lines(output: &RunOutput, config: RenderConfig, plan: &RenderPlan) -> Vec<String> {
+    let sources = if config.tree_source_only {
+        source_paths(&output.file_results)
+    } else {
+        HashSet::new()
+    };
+    let tree_entries = output
+        .repo_entries
+        .iter()
+        .filter(|entry| plan.tree_depth.is_none_or(|depth| entry.depth <= depth))
+        .filter(|entry| !config.tree_source_only || sources.contains(entry.path.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    render_repo_tree(
+        &tree_entries,
+        &output.file_results,
+        TreeOptions {
+            max_files: plan.tree_max_files,
+            max_children: config.tree_max_children,
+            collapse_non_source: config.collapse_non_source_dirs,
+            include_types: config.include_types,
+            hide_internal: config.hide_internal,
+            hide_deprecated: config.hide_deprecated,
+        },
+    )
+}
+
+fn render_functions_section(
+    files: &[&FileResult],
+    config: RenderConfig,
+    plan: &RenderPlan,
+) -> Vec<String> {
+    let mut lines = vec![String::new(), "# Exported Functions".to_string()];
+
+    let files_with_exports = files
+        .iter()
+        .map(|f| {
+            let profile = plan.profile_for(&f.file_path, config.profile);
+            (*f, function_entries(f, config, profile))
+        })
+        .filter(|(_, exports)| !exports.is_empty())
         .collect::<Vec<_>>();
 
     if files_with_exports.is_empty() {
         lines.push("No exported functions found.".to_string());
     } else {
-        for file in files_with_exports {
-            lines.push(String::new());
-            lines.push(format!("### `{}`", file.file_path));
-            for export in &file.function_exports {
+        lines.extend(render_grouped(files_with_exports, config.grouping));
+    }
+    lines
+}
+
+fn render_types_section(
+    files: &[&FileResult],
+    config: RenderConfig,
+    plan: &RenderPlan,
+) -> Vec<String> {
+    let mut lines = vec![String::new(), "# Type Inventory".to_string()];
+    let files_with_types = files
+        .iter()
+        .map(|f| {
+            let profile = plan.profile_for(&f.file_path, config.profile);
+            (*f, type_entries(f, config, profile))
+        })
+        .filter(|(_, types)| !types.is_empty())
+        .collect::<Vec<_>>();
+
+    if files_with_types.is_empty() {
+        lines.push("No exported types or interfaces found.".to_string());
+    } else {
+        lines.extend(render_grouped(files_with_types, config.grouping));
+    }
+    lines
+}
+
+fn render_props_section(
+    files: &[&FileResult],
+    config: RenderConfig,
+    plan: &RenderPlan,
+) -> Vec<String> {
+    let files_with_props = files
+        .iter()
+        .filter(|f| !f.component_props.is_empty())
+        .map(|f| (*f, prop_entries(f, plan.profile_for(&f.file_path, config.profile))))
+        .collect::<Vec<_>>();
+    if files_with_props.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![String::new(), "# Component Props".to_string()];
+    lines.extend(render_grouped(files_with_props, config.grouping));
+    lines
+}
+
+fn render_error_sections(output: &RunOutput) -> Vec<String> {
+    let mut lines = Vec::new();
+    let parse_errors = output
+        .file_results
+        .iter()
+        .filter_map(|f| f.parse_error.as_ref().map(|err| (&f.file_path, err)))
+        .collect::<Vec<_>>();
+
+    if !parse_errors.is_empty() {
+        lines.push(String::new());
+        lines.push("## Parse Errors".to_string());
+        for (path, err) in parse_errors {
+            lines.push(format!("- `{path}`: {err}"));
+            for diagnostic in &err.diagnostics {
                 lines.push(format!(
-                    "- `{}`",
-                    format_function_entry(export, config.profile)
+                    "  - L{}:{} `{}` — `{}`",
+                    diagnostic.line,
+                    diagnostic.column,
+                    diagnostic.kind,
+                    diagnostic.snippet.replace('`', "'")
                 ));
             }
+            if err.omitted > 0 {
+                lines.push(format!("  - … {} more", err.omitted));
+            }
         }
     }
 
-    if config.include_types {
+    if !output.walk_errors.is_empty() {
         lines.push(String::new());
-        lines.push("# Type Inventory".to_string());
-        let files_with_types = output
-            .file_results
+        lines.push("## Walk Errors".to_string());
+        for err in &output.walk_errors {
+            lines.push(format!("- `{}`: {}", err.path, err.message));
+        }
+    }
+    lines
+}
+
+/// One section per user query title, in the order titles first appear.
+fn render_custom_sections(
+    files: &[&FileResult],
+    config: RenderConfig,
+    plan: &RenderPlan,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    for section in query_titles(files.iter().copied()) {
+        lines.push(String::new());
+        lines.push(format!("# {section}"));
+        let files = files
             .iter()
-            .filter(|f| !f.type_exports.is_empty())
+            .map(|f| {
+                let profile = plan.profile_for(&f.file_path, config.profile);
+                (*f, custom_entries(f, section, profile))
+            })
+            .filter(|(_, entries)| !entries.is_empty())
             .collect::<Vec<_>>();
+        lines.extend(render_grouped(files, config.grouping));
+    }
+    lines
+}
 
-        if files_with_types.is_empty() {
-            lines.push("No exported types or interfaces found.".to_string());
-        } else {
-            for file in files_with_types {
-                lines.push(String::new());
-                lines.push(format!("### `{}`", file.file_path));
-                for ty in &file.type_exports {
-                    let value = match config.profile {
-                        RenderProfile::Detailed => format!("{} @L{}", ty.name, ty.line),
-                        _ => ty.name.clone(),
-                    };
-                    lines.push(format!("- `{value}`"));
-                }
-            }
+/// User query titles, in the order they first appear.
+fn query_titles<'a>(files: impl IntoIterator<Item = &'a FileResult>) -> Vec<&'a str> {
+    let mut titles: Vec<&str> = Vec::new();
+    for custom in files.into_iter().flat_map(|f| &f.custom_exports) {
+        if !titles.contains(&custom.section.as_str()) {
+            titles.push(&custom.section);
         }
     }
+    titles
+}
 
-    let parse_errors = output
-        .file_results
+pub(crate) fn custom_line(custom: &crate::CustomExport, profile: RenderProfile) -> String {
+    let kind = custom
+        .kind
+        .as_deref()
+        .map(|kind| format!(" _{kind}_"))
+        .unwrap_or_default();
+    match profile {
+        RenderProfile::Compact => format!("- `{}`", custom.name),
+        RenderProfile::Balanced => format!("- `{}`{kind}", custom.name),
+        RenderProfile::Detailed => {
+            let signature = custom
+                .signature
+                .as_deref()
+                .map(normalize_whitespace)
+                .unwrap_or_else(|| custom.name.clone());
+            format!("- `{}` — `{signature}` @L{}{kind}", custom.name, custom.line)
+        }
+    }
+}
+
+fn is_hidden(doc: Option<&DocComment>, deprecated: Option<&str>, config: RenderConfig) -> bool {
+    is_internal_hidden(doc, config) || (config.hide_deprecated && deprecated.is_some())
+}
+
+fn is_internal_hidden(doc: Option<&DocComment>, config: RenderConfig) -> bool {
+    config.hide_internal && doc.is_some_and(|doc| doc.internal)
+}
+
+/// Collects deprecated exports into a collapsed block at the end of the file so
+/// agents still can look them up without picking them by default.
+fn render_deprecated_section(
+    files: &[&FileResult],
+    config: RenderConfig,
+    plan: &RenderPlan,
+) -> Vec<String> {
+    let mut entries = Vec::new();
+    for file in files.iter().copied() {
+        let profile = plan.profile_for(&file.file_path, config.profile);
+        let file_lines = deprecated_entries(file, config, profile);
+        if !file_lines.is_empty() {
+            entries.push((file, file_lines));
+        }
+    }
+
+    if entries.is_empty() {
+        return Vec::new();
+    }
+
+    let count = entries.iter().map(|(_, lines)| lines.len()).sum::<usize>();
+    let mut lines = vec![
+        String::new(),
+        "# Deprecated Exports".to_string(),
+        "<details>".to_string(),
+        format!(
+            "<summary>{}</summary>",
+            counted(count, "deprecated export", "deprecated exports")
+        ),
+    ];
+    lines.extend(render_grouped(entries, config.grouping));
+    lines.push(String::new());
+    lines.push("</details>".to_string());
+    lines
+}
+
+fn function_entries<'a>(
+    file: &'a FileResult,
+    config: RenderConfig,
+    profile: RenderProfile,
+) -> Vec<Entry<'a>> {
+    by_importance(&file.function_exports, config, |export| export.importance)
+        .filter(|export| !is_hidden(export.doc.as_ref(), export.deprecated.as_deref(), config))
+        .map(|export| Entry {
+            name: &export.name,
+            namespace: export.namespace.as_deref(),
+            line: function_line(export, profile),
+        })
+        .collect()
+}
+
+fn type_entries<'a>(
+    file: &'a FileResult,
+    config: RenderConfig,
+    profile: RenderProfile,
+) -> Vec<Entry<'a>> {
+    by_importance(&file.type_exports, config, |ty| ty.importance)
+        .filter(|ty| !is_hidden(ty.doc.as_ref(), ty.deprecated.as_deref(), config))
+        .map(|ty| Entry {
+            name: &ty.name,
+            namespace: ty.namespace.as_deref(),
+            line: type_line(ty, profile),
+        })
+        .collect()
+}
+
+/// A file's exports in line order, or highest score first when sorting by importance.
+fn by_importance<T>(
+    exports: &[T],
+    config: RenderConfig,
+    importance: fn(&T) -> u32,
+) -> impl Iterator<Item = &T> {
+    let mut exports = exports.iter().collect::<Vec<_>>();
+    if config.sort == SortOrder::Importance {
+        exports.sort_by_key(|export| Reverse(importance(export)));
+    }
+    exports.into_iter()
+}
+
+fn prop_entries(file: &FileResult, profile: RenderProfile) -> Vec<Entry<'_>> {
+    file.component_props
         .iter()
-        .filter_map(|f| f.parse_error.as_ref().map(|err| (&f.file_path, err)))
+        .map(|prop| Entry {
+            name: &prop.name,
+            namespace: None,
+            line: format!("- `{}`", prop_entry(prop, profile)),
+        })
+        .collect()
+}
+
+fn custom_entries<'a>(
+    file: &'a FileResult,
+    section: &str,
+    profile: RenderProfile,
+) -> Vec<Entry<'a>> {
+    file.custom_exports
+        .iter()
+        .filter(|custom| custom.section == section)
+        .map(|custom| Entry {
+            name: &custom.name,
+            namespace: None,
+            line: custom_line(custom, profile),
+        })
+        .collect()
+}
+
+fn deprecated_entries<'a>(
+    file: &'a FileResult,
+    config: RenderConfig,
+    profile: RenderProfile,
+) -> Vec<Entry<'a>> {
+    let mut entries = by_importance(&file.function_exports, config, |export| export.importance)
+        .filter(|export| export.deprecated.is_some())
+        .filter(|export| !is_internal_hidden(export.doc.as_ref(), config))
+        .map(|export| Entry {
+            name: &export.name,
+            namespace: export.namespace.as_deref(),
+            line: function_line(export, profile),
+        })
         .collect::<Vec<_>>();
 
-    if !parse_errors.is_empty() {
+    if config.include_types {
+        entries.extend(
+            by_importance(&file.type_exports, config, |ty| ty.importance)
+                .filter(|ty| ty.deprecated.is_some())
+                .filter(|ty| !is_internal_hidden(ty.doc.as_ref(), config))
+                .map(|ty| Entry {
+                    name: &ty.name,
+                    namespace: ty.namespace.as_deref(),
+                    line: type_line(ty, profile),
+                }),
+        );
+    }
+    entries
+}
+
+/// The lines `file` adds to the configured export sections under `profile`,
+/// leaving out the section headings it shares with other files.
+pub(crate) fn file_lines(
+    file: &FileResult,
+    config: RenderConfig,
+    profile: RenderProfile,
+) -> Vec<String> {
+    let mut groups = Vec::new();
+    for section in config.sections.iter() {
+        match section {
+            Section::Functions => groups.push(function_entries(file, config, profile)),
+            Section::Types if config.include_types => {
+                groups.push(type_entries(file, config, profile));
+            }
+            Section::Props => groups.push(prop_entries(file, profile)),
+            Section::Queries => groups.extend(
+                query_titles([file])
+                    .into_iter()
+                    .map(|title| custom_entries(file, title, profile)),
+            ),
+            Section::Deprecated if config.hide_deprecated => {
+                groups.push(deprecated_entries(file, config, profile));
+            }
+            _ => {}
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|entries| !entries.is_empty())
+        .flat_map(|entries| render_grouped(vec![(file, entries)], config.grouping))
+        .collect()
+}
+
+/// One count line per directory the token budget summarised.
+fn render_summarized_section(output: &RunOutput, plan: &RenderPlan) -> Vec<String> {
+    if plan.summarized.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = summarized_heading();
+    for dir in &plan.summarized {
+        let mut count = DirCount::default();
+        for file in output
+            .file_results
+            .iter()
+            .filter(|f| is_within(&f.file_path, dir))
+        {
+            count.add(file);
+        }
+        lines.push(count.line(dir));
+    }
+    lines
+}
+
+pub(crate) fn summarized_heading() -> Vec<String> {
+    vec![String::new(), "# Summarised Directories".to_string()]
+}
+
+/// What a summarised directory holds.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DirCount {
+    files: usize,
+    functions: usize,
+    types: usize,
+}
+
+impl DirCount {
+    pub(crate) fn add(&mut self, file: &FileResult) {
+        self.files += 1;
+        self.functions += file.function_exports.len();
+        self.types += file.type_exports.len();
+    }
+
+    pub(crate) fn line(&self, dir: &str) -> String {
+        format!(
+            "- `{dir}/`: {}, {}, {}",
+            counted(self.files, "file", "files"),
+            counted(self.functions, "function", "functions"),
+            counted(self.types, "type", "types")
+        )
+    }
+}
+
+/// How many files the budget steps touched, for the notes.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Trimmed {
+    pub(crate) balanced: usize,
+    pub(crate) compact: usize,
+    pub(crate) summarized: usize,
+}
+
+impl Trimmed {
+    fn of(output: &RunOutput, plan: &RenderPlan) -> Self {
+        let mut trimmed = Self::default();
+        for profile in plan.profiles.values() {
+            trimmed.count_profile(*profile, 1);
+        }
+        if !plan.summarized.is_empty() {
+            trimmed.summarized = output
+                .file_results
+                .iter()
+                .filter(|f| plan.is_summarized(&f.file_path))
+                .count();
+        }
+        trimmed
+    }
+
+    pub(crate) fn count_profile(&mut self, profile: RenderProfile, delta: isize) {
+        let count = match profile {
+            RenderProfile::Balanced => &mut self.balanced,
+            RenderProfile::Compact => &mut self.compact,
+            RenderProfile::Detailed => return,
+        };
+        *count = count.saturating_add_signed(delta);
+    }
+}
+
+pub(crate) fn counted(count: usize, one: &str, many: &str) -> String {
+    format!("{count} {}", if count == 1 { one } else { many })
+}
+
+/// Trailing report of what `--max-tokens` elided, so readers know the map is partial.
+pub(crate) fn render_budget_notes(plan: &RenderPlan, trimmed: &Trimmed) -> Vec<String> {
+    let Some(budget) = plan.budget else {
+        return Vec::new();
+    };
+
+    let mut notes = Vec::new();
+    if let Some(depth) = plan.tree_depth {
+        notes.push(format!("- Repository tree cut to depth {depth}."));
+    }
+    match plan.tree_max_files {
+        Some(0) => notes.push("- Tree directories show file counts only.".to_string()),
+        Some(limit) => notes.push(format!("- Tree directories list at most {limit} files.")),
+        None => {}
+    }
+    for (count, name) in [(trimmed.balanced, "balanced"), (trimmed.compact, "compact")] {
+        if count > 0 {
+            notes.push(format!(
+                "- {} rendered with the {name} profile.",
+                counted(count, "file", "files")
+            ));
+        }
+    }
+    if !plan.summarized.is_empty() {
+        notes.push(format!(
+            "- {} ({}) summarised into counts.",
+            counted(plan.summarized.len(), "directory", "directories"),
+            counted(trimmed.summarized, "file", "files")
+        ));
+    }
+    if plan.over_budget {
+        notes.push("- Still over budget after every reduction.".to_string());
+    }
+
+    if notes.is_empty() {
+        return Vec::new();
+    }
+    let mut lines = vec![
+        String::new(),
+        "## Budget Notes".to_string(),
+        format!("Trimmed to fit `--max-tokens {budget}` (estimated at 4 characters per token):"),
+    ];
+    lines.extend(notes);
+    lines
+}
+
+/// One rendered export line, with what the grouping modes place it by.
+struct Entry<'a> {
+    name: &'a str,
+    namespace: Option<&'a str>,
+    line: String,
+}
+
+/// Entries laid out under one heading.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Group {
+    /// `### ` heading text; `None` for the flat symbol listing.
+    pub(crate) heading: Option<String>,
+    pub(crate) lines: Vec<String>,
+    /// Exports in the group; `lines` also holds namespace and file lines.
+    pub(crate) entries: usize,
+}
+
+/// Lays out each file's entries as [`Grouping`] asks, each group after a blank line.
+fn render_grouped(files: Vec<(&FileResult, Vec<Entry<'_>>)>, grouping: Grouping) -> Vec<String> {
+    let mut lines = Vec::new();
+    for group in group_entries(files, grouping) {
         lines.push(String::new());
-        lines.push("## Parse Errors".to_string());
-        for (path, err) in parse_errors {
-            lines.push(format!("- `{path}`: {err}"));
+        if let Some(heading) = group.heading {
+            lines.push(format!("### {heading}"));
         }
+        lines.extend(group.lines);
     }
+    lines
+}
 
-    lines.join("\n") + "\n"
+fn group_entries(files: Vec<(&FileResult, Vec<Entry<'_>>)>, grouping: Grouping) -> Vec<Group> {
+    match grouping {
+        Grouping::File => group_by_heading(files, section_heading)
+            .into_iter()
+            .map(|(heading, entries)| Group {
+                heading: Some(heading),
+                entries: entries.len(),
+                lines: nest_by_namespace(entries),
+            })
+            .collect(),
+        Grouping::Directory => {
+            let files = files
+                .into_iter()
+                .map(|(file, entries)| (file, vec![(file, entries)]))
+                .collect();
+            group_by_heading(files, directory_heading)
+                .into_iter()
+                .map(|(heading, files)| {
+                    let mut group = Group {
+                        heading: Some(heading),
+                        lines: Vec::new(),
+                        entries: 0,
+                    };
+                    for (file, entries) in files {
+                        let name = file.file_path.rsplit('/').next().unwrap_or(&file.file_path);
+                        group.lines.push(format!("- `{name}`"));
+                        group.entries += entries.len();
+                        group.lines.extend(
+                            nest_by_namespace(entries).into_iter().map(|line| indent(&line)),
+                        );
+                    }
+                    group
+                })
+                .collect()
+        }
+        Grouping::Symbol => {
+            let mut entries = files
+                .into_iter()
+                .flat_map(|(file, entries)| entries.into_iter().map(move |entry| (file, entry)))
+                .collect::<Vec<_>>();
+            if entries.is_empty() {
+                return Vec::new();
+            }
+            entries.sort_by(|(a_file, a), (b_file, b)| {
+                a.name
+                    .to_lowercase()
+                    .cmp(&b.name.to_lowercase())
+                    .then(a.name.cmp(b.name))
+                    .then(a_file.file_path.cmp(&b_file.file_path))
+            });
+            vec![Group {
+                heading: None,
+                entries: entries.len(),
+                lines: entries
+                    .into_iter()
+                    .map(|(file, entry)| with_location(file, entry))
+                    .collect(),
+            }]
+        }
+    }
+}
+
+/// Pairs each file's entries with its heading, merging files that share one
+/// (e.g. the files of a Go package) in first-appearance order.
+fn group_by_heading<T>(
+    entries: Vec<(&FileResult, Vec<T>)>,
+    heading: fn(&FileResult) -> String,
+) -> Vec<(String, Vec<T>)> {
+    let mut groups: Vec<(String, Vec<T>)> = Vec::new();
+    for (file, items) in entries {
+        let heading = heading(file);
+        match groups.iter_mut().find(|(existing, _)| *existing == heading) {
+            Some((_, group)) => group.extend(items),
+            None => groups.push((heading, items)),
+        }
+    }
+    groups
+}
+
+/// The file path, or for files that declare a package (Go) the package and its
+/// directory, so all files in that package share one heading.
+fn section_heading(file: &FileResult) -> String {
+    match &file.package {
+        Some(_) => directory_heading(file),
+        None => format!("`{}`", file.file_path),
+    }
+}
+
+fn directory_heading(file: &FileResult) -> String {
+    let dir = file.file_path.rsplit_once('/').map_or(".", |(dir, _)| dir);
+    match &file.package {
+        Some(package) => format!("`package {package}` (`{dir}/`)"),
+        None => format!("`{dir}/`"),
+    }
+}
+
+/// Appends the file (and namespace) to the first line of a flat entry.
+fn with_location(file: &FileResult, entry: Entry<'_>) -> String {
+    let location = match entry.namespace {
+        Some(namespace) => format!(" (`{}`, `namespace {namespace}`)", file.file_path),
+        None => format!(" (`{}`)", file.file_path),
+    };
+    match entry.line.split_once('\n') {
+        Some((first, rest)) => format!("{first}{location}\n{rest}"),
+        None => entry.line + &location,
+    }
+}
+
+fn indent(line: &str) -> String {
+    format!("  {}", line.replace('\n', "\n  "))
+}
+
+/// Groups namespace members under a `namespace Foo` header line, placed where
+/// the namespace's first member appears.
+fn nest_by_namespace(entries: Vec<Entry<'_>>) -> Vec<String> {
+    let mut groups: Vec<(Option<&str>, Vec<String>)> = Vec::new();
+    for entry in entries {
+        match entry.namespace {
+            Some(namespace) => match groups.iter_mut().find(|(ns, _)| *ns == Some(namespace)) {
+                Some((_, members)) => members.push(indent(&entry.line)),
+                None => groups.push((Some(namespace), vec![indent(&entry.line)])),
+            },
+            None => groups.push((None, vec![entry.line])),
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (namespace, members) in groups {
+        if let Some(namespace) = namespace {
+            lines.push(format!("- `namespace {namespace}`"));
+        }
+        lines.extend(members);
+    }
+    lines
+}
+
+pub(crate) fn function_line(export: &crate::FunctionExport, profile: RenderProfile) -> String {
+    let mut line = format!("- `{}`", format_function_entry(export, profile));
+    if profile == RenderProfile::Balanced && !export.overloads.is_empty() {
+        let count = export.overloads.len();
+        let noun = if count == 1 { "overload" } else { "overloads" };
+        line = format!("{line} (+{count} {noun})");
+    }
+    let line = with_ambient(line, export.ambient);
+    let line = with_deprecation(line, export.deprecated.as_deref());
+    let mut line = with_doc_summary(line, export.doc.as_ref(), profile);
+
+    if profile == RenderProfile::Detailed {
+        for overload in &export.overloads {
+            line.push_str(&format!("\n  - `{}`", normalize_whitespace(overload)));
+        }
+    }
+    line
+}
+
+pub(crate) fn type_line(ty: &crate::TypeExport, profile: RenderProfile) -> String {
+    let value = match profile {
+        RenderProfile::Detailed => {
+            let header = ty.signature.as_deref().unwrap_or(&ty.name);
+            format!("{} @L{}", normalize_whitespace(header), ty.line)
+        }
+        _ => ty.name.clone(),
+    };
+    let line = with_ambient(format!("- `{value}`"), ty.ambient);
+    let line = with_deprecation(line, ty.deprecated.as_deref());
+    with_doc_summary(line, ty.doc.as_ref(), profile)
+}
+
+pub(crate) fn prop_entry(prop: &crate::ComponentProp, profile: RenderProfile) -> String {
+    let mut value = prop.name.clone();
+    if profile == RenderProfile::Compact {
+        return value;
+    }
+
+    if let Some(ty) = &prop.type_annotation {
+        value.push_str(&format!(": {}", normalize_whitespace(ty)));
+    }
+    if profile == RenderProfile::Detailed {
+        if let Some(default) = &prop.default_value {
+            value.push_str(&format!(" = {}", normalize_whitespace(default)));
+        }
+        value.push_str(&format!(" @L{}", prop.line));
+    }
+    value
+}
+
+fn with_ambient(line: String, ambient: bool) -> String {
+    if ambient {
+        format!("{line} _ambient_")
+    } else {
+        line
+    }
+}
+
+/// Deprecation is rendered in every profile: it is what stops agents from
+/// reaching for a legacy helper.
+fn with_deprecation(line: String, deprecated: Option<&str>) -> String {
+    match deprecated {
+        Some("") => format!("{line} **deprecated**"),
+        Some(hint) => format!("{line} **deprecated**: {hint}"),
+        None => line,
+    }
+}
+
+/// Appends the doc summary to an entry line; only the detailed profile pays for it.
+fn with_doc_summary(line: String, doc: Option<&DocComment>, profile: RenderProfile) -> String {
+    if profile != RenderProfile::Detailed {
+        return line;
+    }
+
+    match doc.and_then(|doc| doc.summary.as_deref()) {
+        Some(summary) => format!("{line} — {summary}"),
+        None => line,
+    }
 }
 
 fn format_function_entry(export: &crate::FunctionExport, profile: RenderProfile) -> String {
@@ -101,8 +962,51 @@ fn format_function_entry(export: &crate::FunctionExport, profile: RenderProfile)
     }
 }
 
+/// Returns `<generics>(params)` following `name`, dropping the return type and
+/// any leading decorators or `async` marker (Python).
 fn extract_parameters(signature: &str, name: &str) -> Option<String> {
-    let rest = signature.strip_prefix(name)?.trim_start();
+    let rest = match signature.strip_prefix(name) {
+        Some(rest) => rest,
+        None => {
+            let marker = format!(" {name}(");
+            let start = signature.find(&marker)? + marker.len() - 1;
+            &signature[start..]
+        }
+    }
+    .trim_start();
+    let generics_len = generic_parameters_len(rest);
+    let (generics, rest) = rest.split_at(generics_len);
+    let params = parenthesized_prefix(rest.trim_start())?;
+    Some(format!("{generics}{params}"))
+}
+
+/// Byte length of a leading `<...>` (or Go `[...]`) type parameter list, or 0
+/// when absent.
+fn generic_parameters_len(input: &str) -> usize {
+    let (open, close) = match input.chars().next() {
+        Some('<') => ('<', '>'),
+        Some('[') => ('[', ']'),
+        _ => return 0,
+    };
+
+    let mut depth = 0usize;
+    let mut prev = ' ';
+    for (idx, ch) in input.char_indices() {
+        if ch == open {
+            depth += 1;
+        } else if ch == close && prev != '=' {
+            depth = depth.saturating_sub(1);
+            if depth == 0 {
+                return idx + 1;
+            }
+        }
+        prev = ch;
+    }
+
+    0
+}
+
+fn parenthesized_prefix(rest: &str) -> Option<String> {
     let mut chars = rest.char_indices();
     let (start_idx, start_char) = chars.next()?;
     if start_idx != 0 || start_char != '(' {
@@ -128,14 +1032,58 @@ fn normalize_whitespace(input: &str) -> String {
     input.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
-fn render_repo_tree(entries: &[RepoEntry]) -> Vec<String> {
+/// Scanned source files and every directory containing one.
+fn source_paths(file_results: &[FileResult]) -> HashSet<&str> {
+    let mut paths = HashSet::new();
+    for file in file_results {
+        let mut path = file.file_path.as_str();
+        // Stop at the first ancestor already recorded: its own are too.
+        while paths.insert(path) {
+            match path.rsplit_once('/') {
+                Some((dir, _)) => path = dir,
+                None => break,
+            }
+        }
+    }
+    paths
+}
+
+type TreeChild<'a> = (&'a String, &'a TreeNode);
+
+/// Knobs for the repository tree.
+#[derive(Debug, Clone, Copy)]
+struct TreeOptions {
+    /// Files listed per directory before the rest collapse into counts.
+    max_files: Option<usize>,
+    /// Entries of any kind listed per directory.
+    max_children: Option<usize>,
+    /// Draw directories without scanned source files as `dir/ (N files)`.
+    collapse_non_source: bool,
+    include_types: bool,
+    /// Exports the sections leave out are not counted either.
+    hide_internal: bool,
+    hide_deprecated: bool,
+}
+
+impl TreeOptions {
+    fn hides(&self, doc: Option<&DocComment>, deprecated: Option<&str>) -> bool {
+        (self.hide_internal && doc.is_some_and(|doc| doc.internal))
+            || (self.hide_deprecated && deprecated.is_some())
+    }
+}
+
+fn render_repo_tree(
+    entries: &[RepoEntry],
+    file_results: &[FileResult],
+    options: TreeOptions,
+) -> Vec<String> {
     if entries.is_empty() {
         return vec![".".to_string()];
     }
 
     let mut root = TreeNode {
         is_dir: true,
-        children: BTreeMap::new(),
+        ..TreeNode::default()
     };
 
     for entry in entries {
@@ -157,21 +1105,49 @@ fn render_repo_tree(entries: &[RepoEntry]) -> Vec<String> {
                 .entry((*part).to_string())
                 .or_insert_with(|| TreeNode {
                     is_dir: !is_last || entry.is_dir,
-                    children: BTreeMap::new(),
+                    ..TreeNode::default()
                 });
 
             if is_last {
                 current.is_dir = entry.is_dir;
+            } else if !entry.is_dir {
+                current.files += 1;
             }
         }
     }
 
+    // Counts go to every node on the file's path that the tree shows, so
+    // files below the depth limit still count towards their ancestors.
+    for file in file_results {
+        let mut current = &mut root;
+        for part in file.file_path.split('/') {
+            let Some(child) = current.children.get_mut(part) else {
+                break;
+            };
+            child.sources += 1;
+            child.functions += file
+                .function_exports
+                .iter()
+                .filter(|export| !options.hides(export.doc.as_ref(), export.deprecated.as_deref()))
+                .count();
+            child.types += file
+                .type_exports
+                .iter()
+                .filter(|ty| !options.hides(ty.doc.as_ref(), ty.deprecated.as_deref()))
+                .count();
+            child.parse_errors += usize::from(file.parse_error.is_some());
+            current = child;
+        }
+    }
+
     let mut lines = vec![".".to_string()];
-    render_children(&root, "", &mut lines);
+    render_children(&root, "", options, &mut lines);
     lines
 }
 
-fn render_children(node: &TreeNode, prefix: &str, out: &mut Vec<String>) {
+/// Children past the limits collapse into `… N more (.ext)` lines per
+/// extension; children holding source files are kept first.
+fn render_children(node: &TreeNode, prefix: &str, options: TreeOptions, out: &mut Vec<String>) {
     let mut children = node.children.iter().collect::<Vec<_>>();
     children.sort_by(|(a_name, a_node), (b_name, b_node)| {
         b_node
@@ -180,12 +1156,40 @@ fn render_children(node: &TreeNode, prefix: &str, out: &mut Vec<String>) {
             .then(a_name.cmp(b_name))
     });
 
-    let last_index = children.len().saturating_sub(1);
+    let (dirs, files): (Vec<_>, Vec<_>) =
+        children.into_iter().partition(|(_, child)| child.is_dir);
+    let (files, mut hidden) = keep_within(files, options.max_files);
+    let (shown, hidden_children) =
+        keep_within(dirs.into_iter().chain(files).collect(), options.max_children);
+    hidden.extend(hidden_children);
+
+    let mut labels = shown
+        .into_iter()
+        .map(|(name, child)| (name.to_string(), Some(child)))
+        .collect::<Vec<_>>();
+    labels.extend(overflow_labels(&hidden).into_iter().map(|label| (label, None)));
 
-    for (idx, (name, child)) in children.into_iter().enumerate() {
+    let last_index = labels.len().saturating_sub(1);
+    for (idx, (name, child)) in labels.into_iter().enumerate() {
         let is_last = idx == last_index;
         let branch = if is_last { "└──" } else { "├──" };
-        out.push(format!("{prefix}{branch} {name}"));
+        let Some(child) = child else {
+            out.push(format!("{prefix}{branch} {name}"));
+            continue;
+        };
+
+        let collapsed = options.collapse_non_source
+            && child.is_dir
+            && child.sources == 0
+            && !child.children.is_empty();
+        if collapsed {
+            out.push(format!("{prefix}{branch} {name}/ ({} files)", child.files));
+            continue;
+        }
+        out.push(format!(
+            "{prefix}{branch} {name}{}",
+            tree_annotation(child, options.include_types)
+        ));
 
         let next_prefix = if is_last {
             format!("{prefix}    ")
@@ -194,19 +1198,94 @@ fn render_children(node: &TreeNode, prefix: &str, out: &mut Vec<String>) {
         };
 
         if !child.children.is_empty() {
-            render_children(child, &next_prefix, out);
+            render_children(child, &next_prefix, options, out);
         }
     }
 }
 
+/// Splits `children` into the first `limit` (those holding sources first, in
+/// their original order) and the rest.
+fn keep_within<'a>(
+    children: Vec<TreeChild<'a>>,
+    limit: Option<usize>,
+) -> (Vec<TreeChild<'a>>, Vec<TreeChild<'a>>) {
+    let Some(limit) = limit.filter(|&limit| children.len() > limit) else {
+        return (children, Vec::new());
+    };
+
+    let mut order = (0..children.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&idx| children[idx].1.sources == 0);
+    let kept = order.into_iter().take(limit).collect::<Vec<_>>();
+
+    let (mut shown, mut hidden) = (Vec::new(), Vec::new());
+    for (idx, child) in children.into_iter().enumerate() {
+        if kept.contains(&idx) {
+            shown.push(child);
+        } else {
+            hidden.push(child);
+        }
+    }
+    (shown, hidden)
+}
+
+/// `… 2 more directories`, then `… 780 more (.svg)` per extension, largest first.
+fn overflow_labels(hidden: &[TreeChild<'_>]) -> Vec<String> {
+    let mut labels = Vec::new();
+    let dirs = hidden.iter().filter(|(_, child)| child.is_dir).count();
+    if dirs > 0 {
+        let noun = if dirs == 1 { "directory" } else { "directories" };
+        labels.push(format!("… {dirs} more {noun}"));
+    }
+
+    let mut extensions: BTreeMap<&str, usize> = BTreeMap::new();
+    for (name, _) in hidden.iter().filter(|(_, child)| !child.is_dir) {
+        let extension = match name.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => &name[name.len() - ext.len() - 1..],
+            _ => "no extension",
+        };
+        *extensions.entry(extension).or_default() += 1;
+    }
+    let mut extensions = extensions.into_iter().collect::<Vec<_>>();
+    extensions.sort_by(|(a_ext, a_count), (b_ext, b_count)| {
+        b_count.cmp(a_count).then(a_ext.cmp(b_ext))
+    });
+    labels.extend(
+        extensions
+            .into_iter()
+            .map(|(extension, count)| format!("… {count} more ({extension})")),
+    );
+    labels
+}
+
+/// ` (3 functions, 1 type, 1 parse error)`; empty for nodes with none of them.
+fn tree_annotation(node: &TreeNode, include_types: bool) -> String {
+    let counts = [
+        (node.functions, "function", "functions"),
+        (if include_types { node.types } else { 0 }, "type", "types"),
+        (node.parse_errors, "parse error", "parse errors"),
+    ];
+    let parts = counts
+        .into_iter()
+        .filter(|(count, _, _)| *count > 0)
+        .map(|(count, one, many)| counted(count, one, many))
+        .collect::<Vec<_>>();
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::docs::DocComment;
     use crate::{
-        FileResult, FunctionExport, RenderConfig, RenderProfile, RepoEntry, RunOutput, RunSummary,
-        TypeExport,
+        ComponentProp, CustomExport, Diagnostic, FileResult, FunctionExport, Grouping, ParseError,
+        RenderConfig, RenderProfile, RepoEntry, RunOutput, RunSummary, Section, Sections,
+        TypeExport, WalkError,
     };
 
-    use super::render_markdown_with_config;
+    use super::{TreeOptions, render_markdown_with_config, render_repo_tree};
 
     fn sample_output() -> RunOutput {
         RunOutput {
@@ -243,21 +1322,80 @@ mod tests {
                         signature: "a(\n  x: number,\n  y: number,\n) : string".to_string(),
                         file_path: "src/a.ts".to_string(),
                         line: 2,
+                        doc: Some(DocComment {
+                            summary: Some("Builds a label.".to_string()),
+                            ..DocComment::default()
+                        }),
+                        deprecated: None,
+                        overloads: vec![],
+                        ambient: false,
+                        namespace: None,
+                        importance: 0,
                     }],
                     type_exports: vec![TypeExport {
                         name: "User".to_string(),
+                        signature: None,
                         file_path: "src/a.ts".to_string(),
                         line: 10,
+                        doc: Some(DocComment {
+                            internal: true,
+                            ..DocComment::default()
+                        }),
+                        deprecated: None,
+                        ambient: false,
+                        namespace: None,
+                        importance: 0,
                     }],
+                    component_props: vec![],
+                    custom_exports: vec![],
+                    package: None,
                     parse_error: None,
+                    importance: 0,
+                },
+                FileResult {
+                    file_path: "src/b.ts".to_string(),
+                    function_exports: vec![FunctionExport {
+                        name: "formatPrice".to_string(),
+                        signature: "formatPrice(value: number)".to_string(),
+                        file_path: "src/b.ts".to_string(),
+                        line: 3,
+                        doc: None,
+                        deprecated: Some("Use formatMoney instead".to_string()),
+                        overloads: vec![],
+                        ambient: false,
+                        namespace: None,
+                        importance: 0,
+                    }],
+                    type_exports: vec![],
+                    component_props: vec![],
+                    custom_exports: vec![],
+                    package: None,
+                    parse_error: None,
+                    importance: 0,
                 },
                 FileResult {
                     file_path: "src/c.ts".to_string(),
                     function_exports: vec![],
                     type_exports: vec![],
-                    parse_error: Some("syntax parse error".to_string()),
+                    component_props: vec![],
+                    custom_exports: vec![],
+                    package: None,
+                    parse_error: Some(ParseError {
+                        message: "syntax parse error".to_string(),
+                        diagnostics: vec![Diagnostic {
+                            line: 3,
+                            column: 7,
+                            start_byte: 40,
+                            end_byte: 41,
+                            kind: "MISSING )".to_string(),
+                            snippet: "foo(`a`".to_string(),
+                        }],
+                        omitted: 2,
+                    }),
+                    importance: 0,
                 },
             ],
+            walk_errors: vec![],
         }
     }
 
@@ -268,7 +1406,7 @@ mod tests {
             RenderConfig {
                 profile: RenderProfile::Compact,
                 include_types: true,
-                tree_depth: 10,
+                ..RenderConfig::default()
             },
         );
 
@@ -287,7 +1425,7 @@ mod tests {
             RenderConfig {
                 profile: RenderProfile::Balanced,
                 include_types: true,
-                tree_depth: 10,
+                ..RenderConfig::default()
             },
         );
 
@@ -303,14 +1441,248 @@ mod tests {
             RenderConfig {
                 profile: RenderProfile::Detailed,
                 include_types: true,
-                tree_depth: 10,
+                ..RenderConfig::default()
             },
         );
 
-        assert!(markdown.contains("- `a( x: number, y: number, ) : string @L2`"));
+        assert!(markdown.contains("- `a( x: number, y: number, ) : string @L2` — Builds a label."));
         assert!(markdown.contains("- `User @L10`"));
     }
 
+    #[test]
+    fn doc_summary_only_in_detailed_profile() {
+        let markdown = render_markdown_with_config(&sample_output(), RenderConfig::default());
+
+        assert!(!markdown.contains("Builds a label."));
+    }
+
+    #[test]
+    fn marks_deprecated_exports_inline() {
+        let markdown = render_markdown_with_config(&sample_output(), RenderConfig::default());
+
+        assert!(markdown.contains(
+            "- `formatPrice(value: number)` **deprecated**: Use formatMoney instead"
+        ));
+        assert!(!markdown.contains("# Deprecated Exports"));
+    }
+
+    #[test]
+    fn hide_deprecated_moves_them_to_trailing_section() {
+        let markdown = render_markdown_with_config(
+            &sample_output(),
+            RenderConfig {
+                hide_deprecated: true,
+                ..RenderConfig::default()
+            },
+        );
+
+        let functions_start = markdown.find("# Exported Functions").expect("functions");
+        let deprecated_start = markdown.find("# Deprecated Exports").expect("deprecated");
+        let entry = markdown.find("formatPrice").expect("entry");
+        assert!(entry > deprecated_start && deprecated_start > functions_start);
+        assert!(markdown.contains("<summary>1 deprecated export</summary>"));
+    }
+
+    #[test]
+    fn renders_overloads_and_generics_per_profile() {
+        let mut output = sample_output();
+        output.file_results[0].function_exports.push(FunctionExport {
+            name: "parse".to_string(),
+            signature: "parse<T extends Input<U>>(x: string) : A".to_string(),
+            file_path: "src/a.ts".to_string(),
+            line: 5,
+            doc: None,
+            deprecated: None,
+            overloads: vec!["parse(x: Buffer) : B".to_string()],
+            ambient: false,
+            namespace: None,
+            importance: 0,
+        });
+
+        let balanced = render_markdown_with_config(&output, RenderConfig::default());
+        assert!(balanced.contains("- `parse<T extends Input<U>>(x: string)` (+1 overload)"));
+
+        let detailed = render_markdown_with_config(
+            &output,
+            RenderConfig {
+                profile: RenderProfile::Detailed,
+                ..RenderConfig::default()
+            },
+        );
+        assert!(detailed.contains(
+            "- `parse<T extends Input<U>>(x: string) : A @L5`\n  - `parse(x: Buffer) : B`"
+        ));
+    }
+
+    #[test]
+    fn drops_python_decorators_in_balanced_profile() {
+        let mut output = sample_output();
+        output.file_results[0].function_exports[0].signature =
+            "@functools.cache async a(x: int) -> str".to_string();
+
+        let markdown = render_markdown_with_config(&output, RenderConfig::default());
+
+        assert!(markdown.contains("- `a(x: int)`"));
+    }
+
+    #[test]
+    fn groups_go_files_by_package() {
+        let mut output = sample_output();
+        for (idx, path) in ["svc/api/server.go", "svc/api/routes.go"].iter().enumerate() {
+            output.file_results.push(FileResult {
+                file_path: path.to_string(),
+                function_exports: vec![FunctionExport {
+                    name: format!("Route{idx}"),
+                    signature: format!("Route{idx}[T any](r T) error"),
+                    file_path: path.to_string(),
+                    line: 3,
+                    doc: None,
+                    deprecated: None,
+                    overloads: vec![],
+                    ambient: false,
+                    namespace: None,
+                    importance: 0,
+                }],
+                type_exports: vec![],
+                component_props: vec![],
+                custom_exports: vec![],
+                package: Some("api".to_string()),
+                parse_error: None,
+                importance: 0,
+            });
+        }
+
+        let markdown = render_markdown_with_config(&output, RenderConfig::default());
+
+        assert!(markdown.contains(
+            "### `package api` (`svc/api/`)\n- `Route0[T any](r T)`\n- `Route1[T any](r T)`"
+        ));
+        assert_eq!(markdown.matches("`package api`").count(), 1);
+    }
+
+    #[test]
+    fn renders_custom_query_sections() {
+        let mut output = sample_output();
+        output.file_results[1].custom_exports.push(CustomExport {
+            section: "Nitro Routes".to_string(),
+            name: "users".to_string(),
+            signature: Some("defineEventHandler(() =>\n  [])".to_string()),
+            kind: Some("defineEventHandler".to_string()),
+            file_path: "src/b.ts".to_string(),
+            line: 4,
+        });
+
+        let balanced = render_markdown_with_config(&output, RenderConfig::default());
+        let detailed = render_markdown_with_config(
+            &output,
+            RenderConfig {
+                profile: RenderProfile::Detailed,
+                ..RenderConfig::default()
+            },
+        );
+
+        assert!(balanced.contains("# Nitro Routes\n\n### `src/b.ts`\n- `users` _defineEventHandler_"));
+        assert!(detailed.contains(
+            "- `users` — `defineEventHandler(() => [])` @L4 _defineEventHandler_"
+        ));
+    }
+
+    #[test]
+    fn marks_ambient_declarations() {
+        let mut output = sample_output();
+        output.file_results[0].type_exports[0].ambient = true;
+
+        let markdown = render_markdown_with_config(&output, RenderConfig::default());
+
+        assert!(markdown.contains("- `User` _ambient_"));
+    }
+
+    #[test]
+    fn nests_namespace_members_under_header() {
+        let mut output = sample_output();
+        output.file_results[0].function_exports.push(FunctionExport {
+            name: "Geometry.area".to_string(),
+            signature: "Geometry.area(r: number) : number".to_string(),
+            file_path: "src/a.ts".to_string(),
+            line: 4,
+            doc: None,
+            deprecated: None,
+            overloads: vec![],
+            ambient: false,
+            namespace: Some("Geometry".to_string()),
+            importance: 0,
+        });
+
+        let markdown = render_markdown_with_config(&output, RenderConfig::default());
+
+        assert!(markdown.contains(
+            "- `a( x: number, y: number, )`\n- `namespace Geometry`\n  - `Geometry.area(r: number)`"
+        ));
+    }
+
+    #[test]
+    fn renders_component_props_per_profile() {
+        let mut output = sample_output();
+        output.file_results[1].component_props.push(ComponentProp {
+            name: "title".to_string(),
+            type_annotation: Some("string".to_string()),
+            default_value: Some("\"Untitled\"".to_string()),
+            file_path: "src/b.ts".to_string(),
+            line: 7,
+        });
+
+        let balanced = render_markdown_with_config(&output, RenderConfig::default());
+        let detailed = render_markdown_with_config(
+            &output,
+            RenderConfig {
+                profile: RenderProfile::Detailed,
+                ..RenderConfig::default()
+            },
+        );
+
+        assert!(balanced.contains("# Component Props\n\n### `src/b.ts`\n- `title: string`"));
+        assert!(detailed.contains("- `title: string = \"Untitled\" @L7`"));
+        assert!(!render_markdown_with_config(&sample_output(), RenderConfig::default())
+            .contains("# Component Props"));
+    }
+
+    #[test]
+    fn renders_parse_diagnostics() {
+        let markdown = render_markdown_with_config(&sample_output(), RenderConfig::default());
+
+        assert!(markdown.contains(
+            "- `src/c.ts`: syntax parse error\n  - L3:7 `MISSING )` — `foo('a'`\n  - … 2 more"
+        ));
+    }
+
+    #[test]
+    fn lists_walk_errors() {
+        let mut output = sample_output();
+        output.walk_errors.push(WalkError {
+            path: "locked".to_string(),
+            message: "Permission denied (os error 13)".to_string(),
+        });
+
+        let markdown = render_markdown_with_config(&output, RenderConfig::default());
+
+        assert!(markdown.contains("## Walk Errors\n- `locked`: Permission denied (os error 13)"));
+    }
+
+    #[test]
+    fn can_hide_internal_exports() {
+        let markdown = render_markdown_with_config(
+            &sample_output(),
+            RenderConfig {
+                hide_internal: true,
+                ..RenderConfig::default()
+            },
+        );
+
+        assert!(markdown.contains("- `a( x: number, y: number, )`"));
+        assert!(!markdown.contains("- `User`"));
+        assert!(markdown.contains("No exported types or interfaces found."));
+    }
+
     #[test]
     fn can_disable_type_inventory() {
         let markdown = render_markdown_with_config(
@@ -318,10 +1690,198 @@ mod tests {
             RenderConfig {
                 profile: RenderProfile::Balanced,
                 include_types: false,
-                tree_depth: 10,
+                ..RenderConfig::default()
             },
         );
 
         assert!(!markdown.contains("# Type Inventory"));
     }
+
+    #[test]
+    fn collapses_tree_files_past_the_limit() {
+        let entries = ["src", "src/a.ts", "src/b.ts", "src/c.ts", "src/nested"]
+            .into_iter()
+            .map(|path| RepoEntry {
+                path: path.to_string(),
+                is_dir: !path.ends_with(".ts"),
+                depth: path.split('/').count(),
+            })
+            .collect::<Vec<_>>();
+        let options = |max_files| TreeOptions {
+            max_files: Some(max_files),
+            max_children: None,
+            collapse_non_source: false,
+            include_types: true,
+            hide_internal: false,
+            hide_deprecated: false,
+        };
+
+        let tree = render_repo_tree(&entries, &[], options(1)).join("\n");
+        assert_eq!(
+            tree,
+            ".\n└── src\n    ├── nested\n    ├── a.ts\n    └── … 2 more (.ts)"
+        );
+
+        let counts_only = render_repo_tree(&entries, &[], options(0)).join("\n");
+        assert!(counts_only.ends_with("├── nested\n    └── … 3 more (.ts)"));
+    }
+
+    #[test]
+    fn limits_tree_children_by_extension() {
+        let mut output = sample_output();
+        output.repo_entries.push(RepoEntry {
+            path: "src/z".to_string(),
+            is_dir: true,
+            depth: 2,
+        });
+        for idx in 0..5 {
+            output.repo_entries.push(RepoEntry {
+                path: format!("src/icon{idx}.svg"),
+                is_dir: false,
+                depth: 2,
+            });
+        }
+        output.repo_entries.push(RepoEntry {
+            path: "src/LICENSE".to_string(),
+            is_dir: false,
+            depth: 2,
+        });
+
+        let markdown = render_markdown_with_config(
+            &output,
+            RenderConfig {
+                tree_max_children: Some(2),
+                ..RenderConfig::default()
+            },
+        );
+        assert!(markdown.contains(
+            "│   ├── z\n│   ├── a.ts (1 function, 1 type)\n\
+             │   ├── … 5 more (.svg)\n│   └── … 1 more (no extension)\n"
+        ));
+
+        let sources_only = render_markdown_with_config(
+            &output,
+            RenderConfig {
+                tree_source_only: true,
+                ..RenderConfig::default()
+            },
+        );
+        assert!(sources_only.contains(
+            ".\n└── src (2 functions, 1 type, 1 parse error)\n    └── a.ts (1 function, 1 type)\n```"
+        ));
+    }
+
+    #[test]
+    fn annotates_tree_with_export_counts() {
+        let mut output = sample_output();
+        output.repo_entries.extend(
+            ["assets", "assets/icons", "assets/icons/a.svg", "assets/logo.svg"]
+                .into_iter()
+                .map(|path| RepoEntry {
+                    path: path.to_string(),
+                    is_dir: !path.ends_with(".svg"),
+                    depth: path.split('/').count(),
+                }),
+        );
+
+        let markdown = render_markdown_with_config(&output, RenderConfig::default());
+        assert!(markdown.contains(
+            "├── assets\n│   ├── icons\n│   │   └── a.svg\n│   └── logo.svg\n\
+             ├── src (2 functions, 1 type, 1 parse error)\n\
+             │   └── a.ts (1 function, 1 type)\n\
+             └── Cargo.toml\n"
+        ));
+
+        let collapsed = render_markdown_with_config(
+            &output,
+            RenderConfig {
+                include_types: false,
+                collapse_non_source_dirs: true,
+                ..RenderConfig::default()
+            },
+        );
+        assert!(collapsed.contains(
+            "├── assets/ (2 files)\n├── src (2 functions, 1 parse error)\n│   └── a.ts (1 function)\n"
+        ));
+
+        let internal_hidden = render_markdown_with_config(
+            &output,
+            RenderConfig {
+                hide_internal: true,
+                ..RenderConfig::default()
+            },
+        );
+        assert!(internal_hidden.contains(
+            "├── src (2 functions, 1 parse error)\n│   └── a.ts (1 function)\n"
+        ));
+    }
+
+    #[test]
+    fn renders_selected_sections_in_order() {
+        let output = sample_output();
+        let config = RenderConfig {
+            sections: Sections::new(&[
+                Section::Summary,
+                Section::Tree,
+                Section::Types,
+                Section::Functions,
+                Section::Types,
+            ]),
+            ..RenderConfig::default()
+        };
+
+        let markdown = render_markdown_with_config(&output, config);
+        assert!(markdown.starts_with(
+            "# Summary\n- Scanned files: 3\n- Parsed: 2\n- Parse failures: 1\n\
+             - Exported functions: 2\n- Exported types: 1\n\n# Repository Structure\n"
+        ));
+        let types = markdown.find("# Type Inventory").expect("types");
+        let functions = markdown.find("# Exported Functions").expect("functions");
+        assert!(types < functions);
+        assert!(!markdown.contains("## Parse Errors"));
+        let default = render_markdown_with_config(&output, RenderConfig::default());
+        assert!(!default.contains("# Summary"));
+    }
+
+    #[test]
+    fn groups_exports_by_directory_or_symbol() {
+        let mut output = sample_output();
+        output.file_results[0].function_exports[0].name = "parse".to_string();
+        output.file_results[1].file_path = "src/utils/b.ts".to_string();
+        let sections = Sections::new(&[Section::Functions]);
+        let deprecated = "**deprecated**: Use formatMoney instead";
+
+        let by_directory = render_markdown_with_config(
+            &output,
+            RenderConfig {
+                grouping: Grouping::Directory,
+                sections,
+                ..RenderConfig::default()
+            },
+        );
+        assert_eq!(
+            by_directory,
+            format!(
+                "# Exported Functions\n\n### `src/`\n- `a.ts`\n  - `parse`\n\n\
+                 ### `src/utils/`\n- `b.ts`\n  - `formatPrice(value: number)` {deprecated}\n"
+            )
+        );
+
+        let by_symbol = render_markdown_with_config(
+            &output,
+            RenderConfig {
+                grouping: Grouping::Symbol,
+                sections,
+                ..RenderConfig::default()
+            },
+        );
+        assert_eq!(
+            by_symbol,
+            format!(
+                "# Exported Functions\n\n\
+                 - `formatPrice(value: number)` {deprecated} (`src/utils/b.ts`)\n\
+                 - `parse` (`src/a.ts`)\n"
+            )
+        );
+    }
 }
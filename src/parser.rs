@@ -1,46 +1,145 @@
+use std::path::Path;
+
 use tree_sitter::{Node, Parser, Tree};
 
-use crate::walker::SourceKind;
+use crate::docs::{DocComment, parse_doc_comment};
+use crate::language::LanguageExtractor;
+use crate::queries::{QuerySet, UserQuery};
+use crate::walker::{SourceKind, WalkOptions, classify_source_file};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct ExtractedFunction {
     pub name: String,
     pub signature: String,
     pub line: usize,
+    pub doc: Option<DocComment>,
+    pub deprecated: Option<String>,
+    /// Further overload signatures after `signature`, in declaration order.
+    pub overloads: Vec<String>,
+    pub ambient: bool,
+    /// Dotted path of the enclosing namespace; `name` is already qualified.
+    pub namespace: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct ExtractedType {
     pub name: String,
+    /// Declaration header, when it says more than the name (Python class
+    /// decorators and bases).
+    pub signature: Option<String>,
     pub line: usize,
+    pub doc: Option<DocComment>,
+    pub deprecated: Option<String>,
+    pub ambient: bool,
+    pub namespace: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct ExtractedExports {
     pub functions: Vec<ExtractedFunction>,
     pub types: Vec<ExtractedType>,
+    /// Props declared by a component's instance script (Svelte).
+    pub props: Vec<ExtractedProp>,
+    /// Declared package name (Go).
+    pub package: Option<String>,
+    /// Matches of user `.scm` queries.
+    pub custom: Vec<ExtractedCustom>,
+    /// Module specifiers the file imports or re-exports from, used to rank files.
+    pub imports: Vec<ExtractedImport>,
+    /// Where the tree has `ERROR`/`MISSING` nodes. Exports are still extracted
+    /// from every statement that is free of errors.
+    pub syntax_errors: Vec<SyntaxError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedProp {
+    pub name: String,
+    pub type_annotation: Option<String>,
+    pub default_value: Option<String>,
+    pub line: usize,
+}
+
+/// A match of a user query, listed under the query's `section` title.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedCustom {
+    pub section: String,
+    pub name: String,
+    pub signature: Option<String>,
+    pub kind: Option<String>,
+    pub line: usize,
+}
+
+/// A top-level `import ... from "x"`, or with `reexport`, `export ... from "x"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedImport {
+    pub specifier: String,
+    pub reexport: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    /// `ERROR`, or `MISSING <token>` for nodes tree-sitter inserted to recover.
+    pub kind: String,
+    /// The offending source line, trimmed and shortened.
+    pub snippet: String,
 }
 
 pub struct TsExportParser {
     ts_parser: Parser,
     tsx_parser: Parser,
+    js_parser: Parser,
+    ts_queries: QuerySet,
+    tsx_queries: QuerySet,
+    js_queries: QuerySet,
 }
 
 impl TsExportParser {
     pub fn new() -> Result<Self, String> {
+        Self::with_queries(&[])
+    }
+
+    /// Also runs `queries` against every tree whose grammar they compile for;
+    /// each must compile for at least one of TypeScript, TSX and JavaScript.
+    pub fn with_queries(queries: &[UserQuery]) -> Result<Self, String> {
+        let ts_language = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        let tsx_language = tree_sitter_typescript::LANGUAGE_TSX.into();
+        let js_language = tree_sitter_javascript::LANGUAGE.into();
+
         let mut ts_parser = Parser::new();
         ts_parser
-            .set_language(&tree_sitter_typescript::language_typescript())
+            .set_language(&ts_language)
             .map_err(|err| format!("{err}"))?;
 
         let mut tsx_parser = Parser::new();
         tsx_parser
-            .set_language(&tree_sitter_typescript::language_tsx())
+            .set_language(&tsx_language)
+            .map_err(|err| format!("{err}"))?;
+
+        let mut js_parser = Parser::new();
+        js_parser
+            .set_language(&js_language)
             .map_err(|err| format!("{err}"))?;
 
+        let [ts_queries, tsx_queries, js_queries] = QuerySet::compile_each(
+            queries,
+            [
+                ("TypeScript", &ts_language),
+                ("TSX", &tsx_language),
+                ("JavaScript", &js_language),
+            ],
+        )?;
+
         Ok(Self {
             ts_parser,
             tsx_parser,
+            js_parser,
+            ts_queries,
+            tsx_queries,
+            js_queries,
         })
     }
 
@@ -53,19 +152,29 @@ impl TsExportParser {
             SourceKind::Ts => self.extract_exports_from_ts(source),
             SourceKind::Tsx => self.extract_exports_from_tsx(source),
             SourceKind::Vue => self.extract_exports_from_vue(source),
+            SourceKind::Svelte => self.extract_exports_from_svelte(source),
+            SourceKind::Astro => self.extract_exports_from_astro(source),
+            SourceKind::Mdx => self.extract_exports_from_mdx(source),
+            SourceKind::Dts => parse_with(&mut self.ts_parser, &self.ts_queries, source, ExtractMode::AMBIENT),
+            SourceKind::Js | SourceKind::Cjs => {
+                parse_with(&mut self.js_parser, &self.js_queries, source, ExtractMode::COMMONJS)
+            }
+            SourceKind::Jsx | SourceKind::Mjs => {
+                parse_with(&mut self.js_parser, &self.js_queries, source, ExtractMode::ESM)
+            }
         }
     }
 
     fn extract_exports_from_ts(&mut self, source: &str) -> Result<ExtractedExports, String> {
-        parse_with(&mut self.ts_parser, source)
+        parse_with(&mut self.ts_parser, &self.ts_queries, source, ExtractMode::ESM)
     }
 
     fn extract_exports_from_tsx(&mut self, source: &str) -> Result<ExtractedExports, String> {
-        parse_with(&mut self.tsx_parser, source)
+        parse_with(&mut self.tsx_parser, &self.tsx_queries, source, ExtractMode::ESM)
     }
 
     fn extract_exports_from_vue(&mut self, source: &str) -> Result<ExtractedExports, String> {
-        let blocks = extract_vue_scripts(source);
+        let blocks = extract_script_blocks(source);
         let mut all = ExtractedExports::default();
 
         for block in blocks {
@@ -73,34 +182,180 @@ impl TsExportParser {
                 SourceKind::Tsx => self.extract_exports_from_tsx(&block.content),
                 _ => self.extract_exports_from_ts(&block.content),
             }?;
+            block.shift(&mut extracted);
+            all.merge(extracted);
+        }
 
-            for export in &mut extracted.functions {
-                export.line += block.line_offset;
-            }
-            for export in &mut extracted.types {
-                export.line += block.line_offset;
+        all.sort();
+        Ok(all)
+    }
+
+    /// `<script context="module">` (or Svelte 5 `<script module>`) holds the
+    /// component's real exports; in the instance script, `export let` and
+    /// `$props()` declare component props instead.
+    fn extract_exports_from_svelte(&mut self, source: &str) -> Result<ExtractedExports, String> {
+        let mut all = ExtractedExports::default();
+
+        for block in extract_script_blocks(source) {
+            let mut extracted = if block.is_module_context() {
+                self.extract_exports_from_ts(&block.content)?
+            } else {
+                let tree = self
+                    .ts_parser
+                    .parse(&block.content, None)
+                    .ok_or_else(|| "failed to parse file".to_string())?;
+                let mut extracted = ExtractedExports {
+                    props: component_props(tree.root_node(), &block.content),
+                    imports: module_imports(tree.root_node(), &block.content),
+                    ..ExtractedExports::default()
+                };
+                if tree.root_node().has_error() {
+                    collect_syntax_errors(
+                        tree.root_node(),
+                        &block.content,
+                        &mut extracted.syntax_errors,
+                    );
+                }
+                extracted
+            };
+            block.shift(&mut extracted);
+            all.merge(extracted);
+        }
+
+        all.sort();
+        Ok(all)
+    }
+
+    /// Astro frontmatter is TypeScript; its `Props` interface (exported or not)
+    /// describes the component's props.
+    fn extract_exports_from_astro(&mut self, source: &str) -> Result<ExtractedExports, String> {
+        let Some(block) = astro_frontmatter(source) else {
+            return Ok(ExtractedExports::default());
+        };
+
+        let tree = self
+            .ts_parser
+            .parse(&block.content, None)
+            .ok_or_else(|| "failed to parse file".to_string())?;
+        let mut extracted = extract_from_tree(&tree, &block.content, ExtractMode::ESM);
+        extracted.custom = self.ts_queries.run(tree.root_node(), &block.content);
+        if tree.root_node().has_error() {
+            collect_syntax_errors(tree.root_node(), &block.content, &mut extracted.syntax_errors);
+        }
+
+        if let Some(props) = props_declaration(tree.root_node(), &block.content) {
+            if !extracted.types.iter().any(|ty| ty.name == "Props") {
+                extracted.types.extend(type_like_export(props, &block.content));
             }
+            extracted.props = props_members(props, &block.content);
+        }
+
+        block.shift(&mut extracted);
+        extracted.sort();
+        Ok(extracted)
+    }
 
-            all.functions.extend(extracted.functions);
-            all.types.extend(extracted.types);
+    fn extract_exports_from_mdx(&mut self, source: &str) -> Result<ExtractedExports, String> {
+        let mut all = ExtractedExports::default();
+
+        // MDX exports are routinely components, so they parse as TSX.
+        for block in mdx_esm_blocks(source) {
+            let mut extracted = self.extract_exports_from_tsx(&block.content)?;
+            block.shift(&mut extracted);
+            all.merge(extracted);
         }
 
-        all.functions
+        all.sort();
+        Ok(all)
+    }
+}
+
+impl LanguageExtractor for TsExportParser {
+    fn name(&self) -> &str {
+        "typescript"
+    }
+
+    fn handles(&self, path: &Path, options: &WalkOptions) -> bool {
+        classify_source_file(path, options).is_some()
+    }
+
+    fn extract(&mut self, source: &str, path: &Path) -> Result<ExtractedExports, String> {
+        // Anything that reached extraction was opted in, `.d.ts` included.
+        let options = WalkOptions {
+            include_declarations: true,
+        };
+        let kind = classify_source_file(path, &options)
+            .ok_or_else(|| format!("not a TypeScript-family file: {}", path.display()))?;
+        self.extract_exports_for_source(source, &kind)
+    }
+}
+
+impl ExtractedExports {
+    fn merge(&mut self, other: ExtractedExports) {
+        self.functions.extend(other.functions);
+        self.types.extend(other.types);
+        self.props.extend(other.props);
+        self.custom.extend(other.custom);
+        self.imports.extend(other.imports);
+        self.syntax_errors.extend(other.syntax_errors);
+    }
+
+    /// Puts every list in source order, ties by name.
+    pub(crate) fn sort(&mut self) {
+        self.functions
             .sort_by(|a, b| a.line.cmp(&b.line).then(a.name.cmp(&b.name)));
-        all.types
+        self.types
             .sort_by(|a, b| a.line.cmp(&b.line).then(a.name.cmp(&b.name)));
-        Ok(all)
+        self.props.sort_by_key(|prop| prop.line);
+        self.custom.sort_by_key(|custom| custom.line);
     }
 }
 
+/// A `<script>` block carved out of a component file, with the offsets needed
+/// to map positions in `content` back onto the whole file.
 #[derive(Debug, Clone)]
-struct VueScriptBlock {
+struct ScriptBlock {
     content: String,
+    /// Lowercased attributes of the opening tag.
+    attrs: String,
     line_offset: usize,
+    column_offset: usize,
+    byte_offset: usize,
     kind: SourceKind,
 }
 
-fn extract_vue_scripts(source: &str) -> Vec<VueScriptBlock> {
+impl ScriptBlock {
+    fn is_module_context(&self) -> bool {
+        self.attrs.contains("context=\"module\"")
+            || self.attrs.contains("context='module'")
+            || self.attrs.split_whitespace().any(|attr| attr == "module")
+    }
+
+    fn shift(&self, extracted: &mut ExtractedExports) {
+        for export in &mut extracted.functions {
+            export.line += self.line_offset;
+        }
+        for export in &mut extracted.types {
+            export.line += self.line_offset;
+        }
+        for prop in &mut extracted.props {
+            prop.line += self.line_offset;
+        }
+        for custom in &mut extracted.custom {
+            custom.line += self.line_offset;
+        }
+        for error in &mut extracted.syntax_errors {
+            if error.line == 1 {
+                error.column += self.column_offset;
+            }
+            error.line += self.line_offset;
+            error.start_byte += self.byte_offset;
+            error.end_byte += self.byte_offset;
+        }
+    }
+}
+
+fn extract_script_blocks(source: &str) -> Vec<ScriptBlock> {
     let lower = source.to_ascii_lowercase();
     let mut out = Vec::new();
     let mut search_from = 0usize;
@@ -136,11 +391,15 @@ fn extract_vue_scripts(source: &str) -> Vec<VueScriptBlock> {
         };
 
         let line_offset = source[..content_start].bytes().filter(|b| *b == b'\n').count();
+        let line_start = source[..content_start].rfind('\n').map_or(0, |idx| idx + 1);
         let content = source[content_start..content_end].to_string();
 
-        out.push(VueScriptBlock {
+        out.push(ScriptBlock {
             content,
+            attrs: attrs.to_string(),
             line_offset,
+            column_offset: content_start - line_start,
+            byte_offset: content_start,
             kind,
         });
     }
@@ -148,45 +407,339 @@ fn extract_vue_scripts(source: &str) -> Vec<VueScriptBlock> {
     out
 }
 
-fn parse_with(parser: &mut Parser, source: &str) -> Result<ExtractedExports, String> {
+/// The `---` fenced frontmatter at the top of an `.astro` file.
+fn astro_frontmatter(source: &str) -> Option<ScriptBlock> {
+    let mut offset = 0usize;
+    let mut open_end = None;
+
+    for (idx, line) in source.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+
+        match open_end {
+            None if line.trim().is_empty() => {}
+            None if line.trim_end() == "---" => open_end = Some((idx + 1, offset)),
+            None => return None,
+            Some((line_offset, content_start)) if line.trim_end() == "---" => {
+                return Some(ScriptBlock {
+                    content: source[content_start..start].to_string(),
+                    attrs: String::new(),
+                    line_offset,
+                    column_offset: 0,
+                    byte_offset: content_start,
+                    kind: SourceKind::Ts,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    None
+}
+
+/// MDX ESM: paragraphs that start with `import` or `export` at column 0,
+/// outside fenced code blocks. Each runs until the next blank line.
+fn mdx_esm_blocks(source: &str) -> Vec<ScriptBlock> {
+    let mut out = Vec::new();
+    let mut offset = 0usize;
+    let mut in_fence = false;
+    let mut paragraph_start = true;
+    let mut current: Option<(usize, usize)> = None;
+
+    for (idx, line) in source.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+        let blank = line.trim().is_empty();
+
+        if let Some((line_offset, content_start)) = current {
+            if !blank {
+                continue;
+            }
+            out.push(mdx_block(source, line_offset, content_start, start));
+            current = None;
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence
+            && paragraph_start
+            && (line.starts_with("import ") || line.starts_with("export "))
+        {
+            current = Some((idx, start));
+        }
+        paragraph_start = blank;
+    }
+
+    if let Some((line_offset, content_start)) = current {
+        out.push(mdx_block(source, line_offset, content_start, source.len()));
+    }
+
+    out
+}
+
+fn mdx_block(source: &str, line_offset: usize, start: usize, end: usize) -> ScriptBlock {
+    ScriptBlock {
+        content: source[start..end].to_string(),
+        attrs: String::new(),
+        line_offset,
+        column_offset: 0,
+        byte_offset: start,
+        kind: SourceKind::Tsx,
+    }
+}
+
+/// Which export forms count, beyond plain ES module `export` statements.
+#[derive(Debug, Clone, Copy)]
+struct ExtractMode {
+    /// Top-level `declare ...` statements, as published by `.d.ts` files.
+    ambient: bool,
+    /// `module.exports = ...` and `exports.foo = ...` assignments.
+    commonjs: bool,
+}
+
+impl ExtractMode {
+    const ESM: Self = Self {
+        ambient: false,
+        commonjs: false,
+    };
+    const AMBIENT: Self = Self {
+        ambient: true,
+        commonjs: false,
+    };
+    const COMMONJS: Self = Self {
+        ambient: false,
+        commonjs: true,
+    };
+}
+
+fn parse_with(
+    parser: &mut Parser,
+    queries: &QuerySet,
+    source: &str,
+    mode: ExtractMode,
+) -> Result<ExtractedExports, String> {
     let tree = parser
         .parse(source, None)
         .ok_or_else(|| "failed to parse file".to_string())?;
 
+    let mut exports = extract_from_tree(&tree, source, mode);
+    exports.custom = queries.run(tree.root_node(), source);
     if tree.root_node().has_error() {
-        return Err("syntax parse error".to_string());
+        collect_syntax_errors(tree.root_node(), source, &mut exports.syntax_errors);
     }
 
-    Ok(extract_from_tree(&tree, source))
+    Ok(exports)
 }
 
-fn extract_from_tree(tree: &Tree, source: &str) -> ExtractedExports {
+const SNIPPET_MAX_CHARS: usize = 80;
+
+pub(crate) fn collect_syntax_errors(node: Node<'_>, source: &str, out: &mut Vec<SyntaxError>) {
+    if node.is_error() || node.is_missing() {
+        let position = node.start_position();
+        let kind = if node.is_missing() {
+            format!("MISSING {}", node.kind())
+        } else {
+            "ERROR".to_string()
+        };
+        out.push(SyntaxError {
+            line: position.row + 1,
+            column: position.column + 1,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            kind,
+            snippet: line_snippet(source, position.row),
+        });
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.has_error() || child.is_missing() {
+            collect_syntax_errors(child, source, out);
+        }
+    }
+}
+
+fn line_snippet(source: &str, row: usize) -> String {
+    let line = source.lines().nth(row).unwrap_or_default().trim();
+    if line.chars().count() <= SNIPPET_MAX_CHARS {
+        return line.to_string();
+    }
+
+    let shortened = line.chars().take(SNIPPET_MAX_CHARS).collect::<String>();
+    format!("{shortened}…")
+}
+
+fn extract_from_tree(tree: &Tree, source: &str, mode: ExtractMode) -> ExtractedExports {
     let mut exports = ExtractedExports::default();
-    let root = tree.root_node();
+    extract_from_block(
+        tree.root_node(),
+        source,
+        mode.ambient,
+        &Scope::default(),
+        &mut exports,
+    );
+    if mode.commonjs {
+        exports
+            .functions
+            .extend(commonjs_exports(tree.root_node(), source));
+    }
+    exports.imports = module_imports(tree.root_node(), source);
+
+    exports.sort();
+    exports
+}
+
+fn module_imports(root: Node<'_>, source: &str) -> Vec<ExtractedImport> {
     let mut cursor = root.walk();
+    root.named_children(&mut cursor)
+        .filter_map(|statement| {
+            let reexport = match statement.kind() {
+                "import_statement" => false,
+                "export_statement" => true,
+                _ => return None,
+            };
+            let specifier = statement.child_by_field_name("source")?;
+            let specifier = text_for(specifier, source).trim_matches(['"', '\'']);
+            Some(ExtractedImport {
+                specifier: specifier.to_string(),
+                reexport,
+            })
+        })
+        .collect()
+}
+
+/// Where a block of statements lives: the enclosing namespace path, and whether
+/// it is inside a `declare` body, where every member is implicitly exported.
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    namespace: Option<String>,
+    ambient: bool,
+}
+
+impl Scope {
+    fn qualify(&self, name: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{namespace}.{name}"),
+            None => name.to_string(),
+        }
+    }
+}
+
+fn extract_from_block(
+    block: Node<'_>,
+    source: &str,
+    include_ambient: bool,
+    scope: &Scope,
+    exports: &mut ExtractedExports,
+) {
+    let mut cursor = block.walk();
+    // Index of the function whose overload signatures are still being collected.
+    let mut overload_group: Option<usize> = None;
+
+    for child in block.named_children(&mut cursor) {
+        if child.kind() == "comment" {
+            continue;
+        }
 
-    for child in root.children(&mut cursor) {
-        if child.kind() != "export_statement" {
+        // A broken statement yields nothing; its neighbours are still indexed.
+        // Namespace bodies are checked member by member instead.
+        if child.has_error() && !errors_confined_to_namespace_body(child) {
+            overload_group = None;
             continue;
         }
 
-        let Some(exported) = first_named_child(child) else {
+        let exported = match child.kind() {
+            "export_statement" => first_named_child(child),
+            "ambient_declaration" if include_ambient => Some(child),
+            // `namespace Inner {}` without `export` parses as an expression.
+            "expression_statement" if scope.ambient => child.named_child(0),
+            _ if scope.ambient => Some(child),
+            _ => None,
+        };
+        let Some(exported) = exported else {
+            overload_group = None;
             continue;
         };
 
+        let (exported, ambient) = match exported.named_child(0) {
+            Some(inner) if include_ambient && exported.kind() == "ambient_declaration" => {
+                (inner, true)
+            }
+            _ => (exported, scope.ambient),
+        };
+
+        let doc = leading_doc_comment(child, source);
+        let deprecated = doc
+            .as_ref()
+            .and_then(|doc| doc.deprecated.clone())
+            .or_else(|| deprecated_decorator(child, exported, source));
+        let functions_before = exports.functions.len();
+        let types_before = exports.types.len();
+
+        let group = overload_group.take();
+        let open_group = group.filter(|idx| {
+            matches!(exported.kind(), "function_signature" | "function_declaration")
+                && exported.child_by_field_name("name").is_some_and(|name| {
+                    scope.qualify(text_for(name, source)) == exports.functions[*idx].name
+                })
+        });
+
+        if let Some(idx) = open_group {
+            // A doc or `@deprecated` on any overload describes the group.
+            let function = &mut exports.functions[idx];
+            if function.doc.is_none() {
+                function.doc.clone_from(&doc);
+            }
+            if function.deprecated.is_none() {
+                function.deprecated.clone_from(&deprecated);
+            }
+
+            // Further overloads join the group; the implementation signature
+            // is not callable from outside, so it only closes the group.
+            if exported.kind() == "function_signature" {
+                if let Some(extracted) = function_declaration_export(exported, source) {
+                    exports.functions[idx]
+                        .overloads
+                        .push(scope.qualify(&extracted.signature));
+                }
+                overload_group = Some(idx);
+            }
+            continue;
+        }
+
+        let mut nested = None;
         match exported.kind() {
             "function_declaration" => {
                 if let Some(extracted) = function_declaration_export(exported, source) {
                     exports.functions.push(extracted);
                 }
             }
-            "lexical_declaration" => {
-                if is_const_lexical(exported, source) {
-                    exports
-                        .functions
-                        .extend(const_callable_exports(exported, source));
+            "function_signature" => {
+                if let Some(extracted) = function_declaration_export(exported, source) {
+                    overload_group = Some(exports.functions.len());
+                    exports.functions.push(extracted);
                 }
             }
+            "lexical_declaration" | "variable_declaration" if ambient => {
+                exports.functions.extend(ambient_value_exports(exported, source));
+            }
+            "internal_module" | "module" | "statement_block" if ambient => {
+                let module = ambient_module_export(exported, source);
+                nested = Some((exported, module.name.clone()));
+                exports.types.push(module);
+            }
+            "internal_module" | "module" => {
+                if let Some(name) = exported.child_by_field_name("name") {
+                    nested = Some((exported, text_for(name, source).to_string()));
+                }
+            }
+            "lexical_declaration" if is_const_lexical(exported, source) => {
+                exports
+                    .functions
+                    .extend(const_callable_exports(exported, source));
+            }
             "interface_declaration" => {
                 if let Some(extracted) = type_like_export(exported, source) {
                     exports.types.push(extracted);
@@ -197,24 +750,132 @@ fn extract_from_tree(tree: &Tree, source: &str) -> ExtractedExports {
                     exports.types.push(extracted);
                 }
             }
+            // Classes are listed as types; decorators make them deprecated.
+            "class_declaration" | "abstract_class_declaration" => {
+                if let Some(extracted) = type_like_export(exported, source) {
+                    exports.types.push(extracted);
+                }
+            }
             _ => {}
         }
+
+        for export in &mut exports.functions[functions_before..] {
+            export.doc.clone_from(&doc);
+            export.deprecated.clone_from(&deprecated);
+            export.ambient = ambient;
+            export.name = scope.qualify(&export.name);
+            export.signature = scope.qualify(&export.signature);
+            export.namespace.clone_from(&scope.namespace);
+        }
+        for export in &mut exports.types[types_before..] {
+            export.doc.clone_from(&doc);
+            export.deprecated.clone_from(&deprecated);
+            export.ambient = ambient;
+            export.name = scope.qualify(&export.name);
+            export.namespace.clone_from(&scope.namespace);
+        }
+
+        if let Some((module, name)) = nested {
+            // `declare global` has no body field; the block is the node itself.
+            let body = module.child_by_field_name("body").unwrap_or(module);
+            let inner = Scope {
+                namespace: Some(scope.qualify(&name)),
+                ambient,
+            };
+            extract_from_block(body, source, include_ambient, &inner, exports);
+        }
     }
+}
 
-    exports
-        .functions
-        .sort_by(|a, b| a.line.cmp(&b.line).then(a.name.cmp(&b.name)));
-    exports
-        .types
-        .sort_by(|a, b| a.line.cmp(&b.line).then(a.name.cmp(&b.name)));
-    exports
+/// Whether every syntax error under `statement` sits inside the body of the
+/// namespace (or ambient module) it declares.
+fn errors_confined_to_namespace_body(statement: Node<'_>) -> bool {
+    let mut node = statement;
+    loop {
+        match node.kind() {
+            "internal_module" | "module" => {
+                let Some(body) = node.child_by_field_name("body") else {
+                    return false;
+                };
+                let mut cursor = node.walk();
+                return node
+                    .children(&mut cursor)
+                    .all(|child| child == body || !child.has_error());
+            }
+            "export_statement" | "ambient_declaration" | "expression_statement" => {
+                let mut cursor = node.walk();
+                let broken = node
+                    .children(&mut cursor)
+                    .filter(|child| child.has_error())
+                    .collect::<Vec<_>>();
+                match broken[..] {
+                    [only] if !only.is_error() && !only.is_missing() => node = only,
+                    _ => return false,
+                }
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Returns the `/** ... */` block directly above `node`, if any. A blank line
+/// between the comment and the export detaches it.
+fn leading_doc_comment(node: Node<'_>, source: &str) -> Option<DocComment> {
+    let comment = node.prev_sibling()?;
+    if comment.kind() != "comment" {
+        return None;
+    }
+
+    if node.start_position().row > comment.end_position().row + 1 {
+        return None;
+    }
+
+    parse_doc_comment(text_for(comment, source))
+}
+
+/// Looks for a `@deprecated` / `@Deprecated("hint")` decorator on the export
+/// statement or on the declaration itself. The hint is empty when none is given.
+fn deprecated_decorator(statement: Node<'_>, declaration: Node<'_>, source: &str) -> Option<String> {
+    [statement, declaration].into_iter().find_map(|node| {
+        let mut cursor = node.walk();
+        let decorators = node
+            .children(&mut cursor)
+            .filter(|child| child.kind() == "decorator")
+            .collect::<Vec<_>>();
+
+        decorators.into_iter().find_map(|decorator| {
+            let expr = decorator.named_child(0)?;
+            let (callee, arguments) = match expr.kind() {
+                "call_expression" => (
+                    expr.child_by_field_name("function")?,
+                    expr.child_by_field_name("arguments"),
+                ),
+                _ => (expr, None),
+            };
+
+            if !text_for(callee, source).eq_ignore_ascii_case("deprecated") {
+                return None;
+            }
+
+            let hint = arguments
+                .and_then(|args| args.named_child(0))
+                .filter(|arg| arg.kind() == "string" || arg.kind() == "template_string")
+                .map(|arg| {
+                    text_for(arg, source)
+                        .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+                        .to_string()
+                })
+                .unwrap_or_default();
+            Some(hint)
+        })
+    })
 }
 
 fn first_named_child<'a>(node: Node<'a>) -> Option<Node<'a>> {
     let mut cursor = node.walk();
     node.named_children(&mut cursor).find(|child| {
         let kind = child.kind();
-        if kind == "export_clause" || kind == "namespace_export" {
+        if kind == "export_clause" || kind == "namespace_export" || kind == "decorator" {
             return false;
         }
         true
@@ -224,6 +885,7 @@ fn first_named_child<'a>(node: Node<'a>) -> Option<Node<'a>> {
 fn function_declaration_export(node: Node<'_>, source: &str) -> Option<ExtractedFunction> {
     let name_node = node.child_by_field_name("name")?;
     let name = text_for(name_node, source).to_string();
+    let type_parameters = type_parameters_for(node, source);
     let parameters = node
         .child_by_field_name("parameters")
         .map(|n| text_for(n, source).to_string())
@@ -234,15 +896,20 @@ fn function_declaration_export(node: Node<'_>, source: &str) -> Option<Extracted
         .unwrap_or_default();
 
     let signature = if return_type.is_empty() {
-        format!("{name}{parameters}")
+        format!("{name}{type_parameters}{parameters}")
     } else {
-        format!("{name}{parameters} {return_type}")
+        format!("{name}{type_parameters}{parameters} {return_type}")
     };
 
     Some(ExtractedFunction {
         name,
         signature,
         line: name_node.start_position().row + 1,
+        doc: None,
+        deprecated: None,
+        overloads: Vec::new(),
+        ambient: false,
+        namespace: None,
     })
 }
 
@@ -250,10 +917,328 @@ fn type_like_export(node: Node<'_>, source: &str) -> Option<ExtractedType> {
     let name_node = node.child_by_field_name("name")?;
     Some(ExtractedType {
         name: text_for(name_node, source).to_string(),
+        signature: None,
         line: name_node.start_position().row + 1,
+        doc: None,
+        deprecated: None,
+        ambient: false,
+        namespace: None,
     })
 }
 
+/// `declare const VERSION: string` has no value to inspect, so the declared
+/// type stands in for the signature.
+fn ambient_value_exports(node: Node<'_>, source: &str) -> Vec<ExtractedFunction> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|child| child.kind() == "variable_declarator")
+        .filter_map(|declarator| {
+            let name_node = declarator.child_by_field_name("name")?;
+            let name = text_for(name_node, source).to_string();
+            let signature = match declarator.child_by_field_name("type") {
+                Some(ty) => format!("{name}{}", text_for(ty, source)),
+                None => name.clone(),
+            };
+            Some(ExtractedFunction {
+                name,
+                signature,
+                line: name_node.start_position().row + 1,
+                doc: None,
+                deprecated: None,
+                overloads: Vec::new(),
+                ambient: false,
+                namespace: None,
+            })
+        })
+        .collect()
+}
+
+/// `declare namespace Foo`, `declare module 'pkg'` and `declare global`.
+fn ambient_module_export(node: Node<'_>, source: &str) -> ExtractedType {
+    let (name, line) = match node.child_by_field_name("name") {
+        Some(name_node) => (
+            text_for(name_node, source).to_string(),
+            name_node.start_position().row + 1,
+        ),
+        None => ("global".to_string(), node.start_position().row + 1),
+    };
+
+    ExtractedType {
+        name,
+        signature: None,
+        line,
+        doc: None,
+        deprecated: None,
+        ambient: false,
+        namespace: None,
+    }
+}
+
+/// Collects CommonJS exports from top-level assignments:
+/// `module.exports = { foo, bar() {} }`, `module.exports = fn` and
+/// `exports.foo = ...` / `module.exports.foo = ...`. Shorthand and identifier
+/// values resolve against top-level functions; anything else is skipped.
+fn commonjs_exports(root: Node<'_>, source: &str) -> Vec<ExtractedFunction> {
+    let mut out = Vec::new();
+    let local_functions = top_level_functions(root, source);
+    let mut cursor = root.walk();
+
+    for statement in root.named_children(&mut cursor) {
+        if statement.kind() != "expression_statement" || statement.has_error() {
+            continue;
+        }
+        let Some(assignment) = statement
+            .named_child(0)
+            .filter(|node| node.kind() == "assignment_expression")
+        else {
+            continue;
+        };
+        let (Some(left), Some(right)) = (
+            assignment.child_by_field_name("left"),
+            assignment.child_by_field_name("right"),
+        ) else {
+            continue;
+        };
+
+        match text_for(left, source) {
+            "module.exports" if right.kind() == "object" => {
+                let mut members = right.walk();
+                for member in right.named_children(&mut members) {
+                    let export = match member.kind() {
+                        "pair" => member.child_by_field_name("key").and_then(|key| {
+                            let value = member.child_by_field_name("value")?;
+                            commonjs_value(text_for(key, source), key, value, source, &local_functions)
+                        }),
+                        "shorthand_property_identifier" => commonjs_value(
+                            text_for(member, source),
+                            member,
+                            member,
+                            source,
+                            &local_functions,
+                        ),
+                        "method_definition" => member.child_by_field_name("name").map(|name| {
+                            build_from_function_expr(
+                                text_for(name, source).to_string(),
+                                name,
+                                member,
+                                source,
+                            )
+                        }),
+                        _ => None,
+                    };
+                    out.extend(export);
+                }
+            }
+            "module.exports" => {
+                out.extend(commonjs_value("module.exports", left, right, source, &local_functions));
+            }
+            target => {
+                let name = target
+                    .strip_prefix("module.exports.")
+                    .or_else(|| target.strip_prefix("exports."))
+                    .filter(|name| !name.contains('.'));
+                if let Some(name) = name {
+                    out.extend(commonjs_value(name, left, right, source, &local_functions));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn commonjs_value(
+    name: &str,
+    name_node: Node<'_>,
+    value: Node<'_>,
+    source: &str,
+    local_functions: &[(String, Node<'_>)],
+) -> Option<ExtractedFunction> {
+    let name = name.to_string();
+    match value.kind() {
+        "arrow_function" => Some(build_from_arrow(name, name_node, value, source)),
+        "function_expression" => Some(build_from_function_expr(name, name_node, value, source)),
+        "identifier" | "shorthand_property_identifier" => {
+            let local = text_for(value, source);
+            let (_, function) = local_functions.iter().find(|(n, _)| n == local)?;
+            match function.kind() {
+                "arrow_function" => Some(build_from_arrow(name, name_node, *function, source)),
+                _ => Some(build_from_function_expr(name, name_node, *function, source)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Top-level `function foo() {}` and `const foo = () => {}` definitions,
+/// which CommonJS files typically export by reference.
+fn top_level_functions<'a>(root: Node<'a>, source: &str) -> Vec<(String, Node<'a>)> {
+    let mut out = Vec::new();
+    let mut cursor = root.walk();
+
+    for statement in root.named_children(&mut cursor) {
+        match statement.kind() {
+            "function_declaration" => {
+                if let Some(name) = statement.child_by_field_name("name") {
+                    out.push((text_for(name, source).to_string(), statement));
+                }
+            }
+            "lexical_declaration" | "variable_declaration" => {
+                let mut declarators = statement.walk();
+                for declarator in statement.named_children(&mut declarators) {
+                    let (Some(name), Some(value)) = (
+                        declarator.child_by_field_name("name"),
+                        declarator.child_by_field_name("value"),
+                    ) else {
+                        continue;
+                    };
+                    if matches!(value.kind(), "arrow_function" | "function_expression") {
+                        out.push((text_for(name, source).to_string(), value));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    out
+}
+
+/// Svelte props: `export let name: T = default` (Svelte 3/4) and
+/// `let { a, b = 1 }: Props = $props()` destructuring (Svelte 5).
+fn component_props(root: Node<'_>, source: &str) -> Vec<ExtractedProp> {
+    let mut out = Vec::new();
+    let mut cursor = root.walk();
+
+    for statement in root.named_children(&mut cursor) {
+        if statement.has_error() {
+            continue;
+        }
+
+        let (declaration, exported) = match statement.kind() {
+            "export_statement" => match first_named_child(statement) {
+                Some(declaration) => (declaration, true),
+                None => continue,
+            },
+            _ => (statement, false),
+        };
+        if !matches!(declaration.kind(), "lexical_declaration" | "variable_declaration") {
+            continue;
+        }
+
+        let mut declarators = declaration.walk();
+        for declarator in declaration
+            .named_children(&mut declarators)
+            .filter(|child| child.kind() == "variable_declarator")
+        {
+            let Some(name) = declarator.child_by_field_name("name") else {
+                continue;
+            };
+            let value = declarator.child_by_field_name("value");
+
+            if exported && name.kind() == "identifier" && !is_const_lexical(declaration, source) {
+                out.push(ExtractedProp {
+                    name: text_for(name, source).to_string(),
+                    type_annotation: declarator
+                        .child_by_field_name("type")
+                        .map(|ty| type_annotation_text(ty, source)),
+                    default_value: value.map(|v| text_for(v, source).to_string()),
+                    line: name.start_position().row + 1,
+                });
+            } else if name.kind() == "object_pattern" && value.is_some_and(|v| is_props_rune(v, source)) {
+                out.extend(destructured_props(name, source));
+            }
+        }
+    }
+
+    out
+}
+
+/// Top-level `interface Props` or `type Props = { ... }`, exported or not.
+fn props_declaration<'a>(root: Node<'a>, source: &str) -> Option<Node<'a>> {
+    let mut cursor = root.walk();
+    root.named_children(&mut cursor)
+        .map(|statement| match statement.kind() {
+            "export_statement" => first_named_child(statement).unwrap_or(statement),
+            _ => statement,
+        })
+        .filter(|node| matches!(node.kind(), "interface_declaration" | "type_alias_declaration"))
+        .find(|node| {
+            node.child_by_field_name("name")
+                .is_some_and(|name| text_for(name, source) == "Props")
+        })
+}
+
+fn props_members(declaration: Node<'_>, source: &str) -> Vec<ExtractedProp> {
+    let Some(body) = declaration
+        .child_by_field_name("body")
+        .or_else(|| declaration.child_by_field_name("value"))
+    else {
+        return Vec::new();
+    };
+
+    let mut cursor = body.walk();
+    body.named_children(&mut cursor)
+        .filter(|member| member.kind() == "property_signature")
+        .filter_map(|member| {
+            let name = member.child_by_field_name("name")?;
+            Some(ExtractedProp {
+                name: text_for(name, source).to_string(),
+                type_annotation: member
+                    .child_by_field_name("type")
+                    .map(|ty| type_annotation_text(ty, source)),
+                default_value: None,
+                line: name.start_position().row + 1,
+            })
+        })
+        .collect()
+}
+
+fn is_props_rune(node: Node<'_>, source: &str) -> bool {
+    node.kind() == "call_expression"
+        && node
+            .child_by_field_name("function")
+            .is_some_and(|callee| text_for(callee, source) == "$props")
+}
+
+fn destructured_props(pattern: Node<'_>, source: &str) -> Vec<ExtractedProp> {
+    let mut cursor = pattern.walk();
+    pattern
+        .named_children(&mut cursor)
+        .filter_map(|member| {
+            let (name, default_value) = match member.kind() {
+                "shorthand_property_identifier_pattern" => (member, None),
+                "object_assignment_pattern" => (
+                    member.child_by_field_name("left")?,
+                    member.child_by_field_name("right"),
+                ),
+                "pair_pattern" => {
+                    let default_value = member
+                        .child_by_field_name("value")
+                        .filter(|value| value.kind() == "assignment_pattern")
+                        .and_then(|value| value.child_by_field_name("right"));
+                    (member.child_by_field_name("key")?, default_value)
+                }
+                _ => return None,
+            };
+            Some(ExtractedProp {
+                name: text_for(name, source).to_string(),
+                type_annotation: None,
+                default_value: default_value.map(|v| text_for(v, source).to_string()),
+                line: name.start_position().row + 1,
+            })
+        })
+        .collect()
+}
+
+/// `: string` -> `string`
+fn type_annotation_text(node: Node<'_>, source: &str) -> String {
+    text_for(node, source)
+        .trim_start_matches(':')
+        .trim()
+        .to_string()
+}
+
 fn is_const_lexical(node: Node<'_>, source: &str) -> bool {
     let mut cursor = node.walk();
     let mut children = node.children(&mut cursor);
@@ -290,7 +1275,7 @@ fn const_callable_exports(node: Node<'_>, source: &str) -> Vec<ExtractedFunction
             "arrow_function" => {
                 out.push(build_from_arrow(name, name_node, value_node, source));
             }
-            "function" => {
+            "function_expression" => {
                 out.push(build_from_function_expr(name, name_node, value_node, source));
             }
             _ => {}
@@ -306,6 +1291,7 @@ fn build_from_arrow(
     node: Node<'_>,
     source: &str,
 ) -> ExtractedFunction {
+    let type_parameters = type_parameters_for(node, source);
     let raw_params = node
         .child_by_field_name("parameters")
         .or_else(|| node.child_by_field_name("parameter"))
@@ -324,15 +1310,20 @@ fn build_from_arrow(
         .unwrap_or_default();
 
     let signature = if return_type.is_empty() {
-        format!("{name}{parameters}")
+        format!("{name}{type_parameters}{parameters}")
     } else {
-        format!("{name}{parameters} {return_type}")
+        format!("{name}{type_parameters}{parameters} {return_type}")
     };
 
     ExtractedFunction {
         name,
         signature,
         line: name_node.start_position().row + 1,
+        doc: None,
+        deprecated: None,
+        overloads: Vec::new(),
+        ambient: false,
+        namespace: None,
     }
 }
 
@@ -342,6 +1333,7 @@ fn build_from_function_expr(
     node: Node<'_>,
     source: &str,
 ) -> ExtractedFunction {
+    let type_parameters = type_parameters_for(node, source);
     let parameters = node
         .child_by_field_name("parameters")
         .map(|n| text_for(n, source).to_string())
@@ -352,19 +1344,31 @@ fn build_from_function_expr(
         .unwrap_or_default();
 
     let signature = if return_type.is_empty() {
-        format!("{name}{parameters}")
+        format!("{name}{type_parameters}{parameters}")
     } else {
-        format!("{name}{parameters} {return_type}")
+        format!("{name}{type_parameters}{parameters} {return_type}")
     };
 
     ExtractedFunction {
         name,
         signature,
         line: name_node.start_position().row + 1,
+        doc: None,
+        deprecated: None,
+        overloads: Vec::new(),
+        ambient: false,
+        namespace: None,
     }
 }
 
-fn text_for<'a>(node: Node<'_>, source: &'a str) -> &'a str {
+fn type_parameters_for(node: Node<'_>, source: &str) -> String {
+    node.child_by_field_name("type_parameters")
+        .map(|n| text_for(n, source).to_string())
+        .unwrap_or_default()
+}
+
+/// The source text a syntax node spans.
+pub(crate) fn text_for<'a>(node: Node<'_>, source: &'a str) -> &'a str {
     let range = node.byte_range();
     &source[range]
 }
@@ -451,6 +1455,513 @@ export interface VueDto { id: string }
         assert_eq!(exports.types[0].line, 7);
     }
 
+    #[test]
+    fn attaches_leading_doc_comments() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"
+/**
+ * Greets someone.
+ * @param name who to greet
+ * @internal
+ */
+export function greet(name: string) {}
+
+/** Detached by the blank line. */
+
+export type Id = string;
+// not a doc comment
+export const noop = () => {};
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Ts)
+            .expect("extract");
+
+        let greet = exports.functions[0].doc.as_ref().expect("greet doc");
+        assert_eq!(greet.summary.as_deref(), Some("Greets someone."));
+        assert_eq!(greet.params[0].name, "name");
+        assert!(greet.internal);
+        assert!(exports.types[0].doc.is_none());
+        assert!(exports.functions[1].doc.is_none());
+    }
+
+    #[test]
+    fn flags_deprecated_exports_from_docs_and_decorators() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"
+/** @deprecated Use formatMoney instead */
+export function formatPrice(value: number) {}
+export type Money = number;
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Ts)
+            .expect("extract");
+
+        assert_eq!(
+            exports.functions[0].deprecated.as_deref(),
+            Some("Use formatMoney instead")
+        );
+        assert!(exports.types[0].deprecated.is_none());
+
+        let decorated = r#"@Deprecated("Use Next")
+export class Legacy {}
+export @deprecated class Bare {}
+export abstract class Current {}
+"#;
+        let exports = parser
+            .extract_exports_for_source(decorated, &SourceKind::Ts)
+            .expect("extract");
+        let classes = exports
+            .types
+            .iter()
+            .map(|ty| (ty.name.as_str(), ty.deprecated.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            classes,
+            vec![
+                ("Legacy", Some("Use Next")),
+                ("Bare", Some("")),
+                ("Current", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_overload_signatures() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"
+/** Parses input. */
+export function parse(x: string): A;
+export function parse(x: Buffer): B;
+export function parse(x: any): any { return x }
+export function other(): void {}
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Ts)
+            .expect("extract");
+
+        assert_eq!(exports.functions.len(), 2);
+        let parse = &exports.functions[0];
+        assert_eq!(parse.signature, "parse(x: string) : A");
+        assert_eq!(parse.overloads, vec!["parse(x: Buffer) : B".to_string()]);
+        assert_eq!(parse.line, 3);
+        assert!(parse.doc.is_some());
+        assert_eq!(exports.functions[1].name, "other");
+        assert!(exports.functions[1].overloads.is_empty());
+
+        let later = r#"
+export function load(x: string): A;
+/** @deprecated Pass a string */
+export function load(x: Buffer): B;
+export function load(x: any): any { return x }
+"#;
+        let exports = parser
+            .extract_exports_for_source(later, &SourceKind::Ts)
+            .expect("extract");
+        assert_eq!(exports.functions.len(), 1);
+        assert_eq!(
+            exports.functions[0].deprecated.as_deref(),
+            Some("Pass a string")
+        );
+    }
+
+    #[test]
+    fn keeps_generic_type_parameters() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = "export function pick<T extends Foo, K>(x: T): K {}\nexport const id = <T,>(x: T): T => x;\n";
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Ts)
+            .expect("extract");
+
+        assert_eq!(exports.functions[0].signature, "pick<T extends Foo, K>(x: T) : K");
+        assert_eq!(exports.functions[1].signature, "id<T,>(x: T) : T");
+    }
+
+    #[test]
+    fn records_ambient_declarations_in_dts() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"declare function foo(x: string): void;
+declare const VERSION: string;
+declare namespace Geo { function area(): number }
+declare module 'vue' { interface ComponentCustomProperties { $t: string } }
+declare global { interface Window { x: 1 } }
+export declare function bar(): void;
+export interface Plain {}
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Dts)
+            .expect("extract");
+
+        let functions = exports
+            .functions
+            .iter()
+            .map(|f| (f.signature.as_str(), f.ambient))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            functions,
+            vec![
+                ("foo(x: string) : void", true),
+                ("VERSION: string", true),
+                ("Geo.area() : number", true),
+                ("bar() : void", true),
+            ]
+        );
+
+        let types = exports
+            .types
+            .iter()
+            .map(|t| (t.name.as_str(), t.ambient))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                ("Geo", true),
+                ("'vue'", true),
+                ("'vue'.ComponentCustomProperties", true),
+                ("global", true),
+                ("global.Window", true),
+                ("Plain", false),
+            ]
+        );
+
+        let plain_ts = parser
+            .extract_exports_for_source(source, &SourceKind::Ts)
+            .expect("extract");
+        assert!(plain_ts.functions.is_empty());
+        assert_eq!(plain_ts.types.len(), 1);
+    }
+
+    #[test]
+    fn qualifies_namespace_members() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"
+export namespace Geometry {
+  export function area(r: number): number { return r }
+  export interface Point { x: number }
+  function hidden() {}
+  export namespace Inner {
+    export const unit = () => 1;
+  }
+}
+namespace Private {
+  export function nope() {}
+}
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Ts)
+            .expect("extract");
+
+        let functions = exports
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.signature.as_str(), f.namespace.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            functions,
+            vec![
+                ("Geometry.area", "Geometry.area(r: number) : number", Some("Geometry")),
+                ("Geometry.Inner.unit", "Geometry.Inner.unit()", Some("Geometry.Inner")),
+            ]
+        );
+        assert_eq!(exports.types.len(), 1);
+        assert_eq!(exports.types[0].name, "Geometry.Point");
+        assert_eq!(exports.types[0].namespace.as_deref(), Some("Geometry"));
+    }
+
+    #[test]
+    fn extracts_valid_exports_around_syntax_errors() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"export function before(a: string) {}
+export function broken( {
+export interface After { id: string }
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Ts)
+            .expect("extract");
+
+        assert_eq!(exports.functions.len(), 1);
+        assert_eq!(exports.functions[0].name, "before");
+        assert!(!exports.syntax_errors.is_empty());
+        assert!(exports.syntax_errors.iter().all(|e| e.line >= 2));
+        let first = &exports.syntax_errors[0];
+        assert_eq!(first.kind, "ERROR");
+        assert_eq!(first.snippet, "export function broken( {");
+        assert_eq!(&source[first.start_byte..first.start_byte + 6], "export");
+
+        let clean = parser
+            .extract_exports_for_source("export type Ok = 1;", &SourceKind::Ts)
+            .expect("extract");
+        assert!(clean.syntax_errors.is_empty());
+
+        let namespaced = r#"export namespace Geo {
+  export function area(r: number): number { return r }
+  export const broken = ;
+  export interface Point { x: number }
+}
+"#;
+        let exports = parser
+            .extract_exports_for_source(namespaced, &SourceKind::Ts)
+            .expect("extract");
+        assert_eq!(exports.functions.len(), 1);
+        assert_eq!(exports.functions[0].name, "Geo.area");
+        assert_eq!(exports.types[0].name, "Geo.Point");
+        assert_eq!(exports.syntax_errors[0].line, 3);
+    }
+
+    #[test]
+    fn offsets_vue_syntax_errors_to_file_positions() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = "<template/>\n<script lang=\"ts\">\nexport const ok = () => 1;\nexport function bad( {\n</script>\n";
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Vue)
+            .expect("extract");
+
+        assert_eq!(exports.functions.len(), 1);
+        let first = &exports.syntax_errors[0];
+        assert_eq!(first.line, 4);
+        assert_eq!(first.snippet, "export function bad( {");
+        assert!(source[first.start_byte..].starts_with("export function bad"));
+    }
+
+    #[test]
+    fn detects_exported_const_function_expression() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = "export const format = function (value: number): string { return '' };";
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Ts)
+            .expect("extract");
+
+        assert_eq!(exports.functions.len(), 1);
+        assert_eq!(exports.functions[0].signature, "format(value: number) : string");
+    }
+
+    #[test]
+    fn detects_javascript_esm_exports() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"
+/** Renders a badge. */
+export function Badge({ label }) { return <span>{label}</span>; }
+export const sum = (a, b) => a + b;
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Jsx)
+            .expect("extract");
+
+        let signatures = exports
+            .functions
+            .iter()
+            .map(|f| f.signature.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(signatures, vec!["Badge({ label })", "sum(a, b)"]);
+        assert!(exports.functions[0].doc.is_some());
+        assert!(exports.syntax_errors.is_empty());
+    }
+
+    #[test]
+    fn detects_commonjs_exports() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"
+function foo(a) {}
+const helper = (b, c) => b;
+const VERSION = "1";
+module.exports = { foo, helper: helper, inline: (x) => x, method(y) {}, VERSION };
+exports.one = function (a, b) {};
+module.exports.two = (c) => c;
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Cjs)
+            .expect("extract");
+
+        let signatures = exports
+            .functions
+            .iter()
+            .map(|f| f.signature.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            signatures,
+            vec![
+                "foo(a)",
+                "helper(b, c)",
+                "inline(x)",
+                "method(y)",
+                "one(a, b)",
+                "two(c)"
+            ]
+        );
+
+        let esm_only = parser
+            .extract_exports_for_source(source, &SourceKind::Mjs)
+            .expect("extract");
+        assert!(esm_only.functions.is_empty());
+    }
+
+    #[test]
+    fn detects_module_exports_function() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = "module.exports = function main(opts) {};\n";
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Js)
+            .expect("extract");
+
+        assert_eq!(exports.functions.len(), 1);
+        assert_eq!(exports.functions[0].name, "module.exports");
+        assert_eq!(exports.functions[0].signature, "module.exports(opts)");
+    }
+
+    #[test]
+    fn extracts_svelte_module_exports_and_props() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"<script context="module" lang="ts">
+  export function preload(url: string) {}
+  export interface Item { id: string }
+</script>
+
+<script lang="ts">
+  export let title: string = "Untitled";
+  export let count;
+  export const readonlyValue = 1;
+  let local = 2;
+</script>
+
+<h1>{title}</h1>
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Svelte)
+            .expect("extract");
+
+        assert_eq!(exports.functions.len(), 1);
+        assert_eq!(exports.functions[0].name, "preload");
+        assert_eq!(exports.functions[0].line, 2);
+        assert_eq!(exports.types[0].name, "Item");
+
+        let props = exports
+            .props
+            .iter()
+            .map(|p| {
+                (
+                    p.name.as_str(),
+                    p.type_annotation.as_deref(),
+                    p.default_value.as_deref(),
+                    p.line,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            props,
+            vec![
+                ("title", Some("string"), Some("\"Untitled\""), 7),
+                ("count", None, None, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_svelte_5_props_rune() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"<script module>
+  export const helper = () => 1;
+</script>
+<script lang="ts">
+  let { label, size = "md", onClick: handle, ...rest }: Props = $props();
+</script>
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Svelte)
+            .expect("extract");
+
+        assert_eq!(exports.functions[0].name, "helper");
+        let props = exports
+            .props
+            .iter()
+            .map(|p| (p.name.as_str(), p.default_value.as_deref(), p.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            props,
+            vec![
+                ("label", None, 5),
+                ("size", Some("\"md\""), 5),
+                ("onClick", None, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_astro_frontmatter_and_props() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"---
+import Layout from "../layouts/Layout.astro";
+
+interface Props {
+  title: string;
+  tags?: string[];
+}
+
+export function slugify(value: string) {
+  return value.toLowerCase();
+}
+const { title } = Astro.props;
+---
+<Layout title={title} />
+<script>
+  export function clientOnly() {}
+</script>
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Astro)
+            .expect("extract");
+
+        assert_eq!(exports.functions.len(), 1);
+        assert_eq!(exports.functions[0].name, "slugify");
+        assert_eq!(exports.functions[0].line, 9);
+        assert_eq!(exports.types[0].name, "Props");
+        assert_eq!(exports.types[0].line, 4);
+        let props = exports
+            .props
+            .iter()
+            .map(|p| (p.name.as_str(), p.type_annotation.as_deref(), p.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            props,
+            vec![("title", Some("string"), 5), ("tags", Some("string[]"), 6)]
+        );
+    }
+
+    #[test]
+    fn extracts_mdx_esm_blocks() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"import { Chart } from "./chart";
+export const meta = { title: "Guide" };
+
+# Guide
+
+Prose can mention export without starting a block.
+
+```ts
+export function fenced() {}
+```
+
+export function formatLabel(value: string) {
+  return value;
+}
+
+export type Tone = "info" | "warn";
+
+export const Callout = ({ tone }: { tone: Tone }) => <aside data-tone={tone} />;
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Mdx)
+            .expect("extract");
+
+        let functions = exports
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.line))
+            .collect::<Vec<_>>();
+        assert_eq!(functions, vec![("formatLabel", 12), ("Callout", 18)]);
+        assert_eq!(exports.types[0].name, "Tone");
+        assert_eq!(exports.types[0].line, 16);
+        assert!(exports.syntax_errors.is_empty());
+    }
+
     #[test]
     fn ignores_non_exported_and_reexports() {
         let mut parser = TsExportParser::new().expect("parser");
@@ -466,4 +1977,28 @@ export type { ImportedType } from "./dep"
         assert!(exports.functions.is_empty());
         assert!(exports.types.is_empty());
     }
+
+    #[test]
+    fn records_import_and_reexport_specifiers() {
+        let mut parser = TsExportParser::new().expect("parser");
+        let source = r#"
+import { a } from "./a";
+import type { B } from '../b';
+export * from "./c";
+export function local() {}
+"#;
+        let exports = parser
+            .extract_exports_for_source(source, &SourceKind::Ts)
+            .expect("extract");
+
+        let imports = exports
+            .imports
+            .iter()
+            .map(|import| (import.specifier.as_str(), import.reexport))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            imports,
+            vec![("./a", false), ("../b", false), ("./c", true)]
+        );
+    }
 }
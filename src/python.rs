@@ -0,0 +1,347 @@
+use std::path::Path;
+
+use tree_sitter::{Node, Parser};
+
+use crate::docs::DocComment;
+use crate::language::LanguageExtractor;
+use crate::parser::{
+    ExtractedExports, ExtractedFunction, ExtractedType, collect_syntax_errors, text_for,
+};
+use crate::walker::WalkOptions;
+
+/// Indexes the public surface of a Python module: module-level functions as
+/// function exports and classes as type exports.
+pub struct PythonExportParser {
+    parser: Parser,
+}
+
+impl PythonExportParser {
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_python::LANGUAGE.into())
+            .map_err(|err| format!("{err}"))?;
+
+        Ok(Self { parser })
+    }
+
+    pub fn extract_exports(&mut self, source: &str) -> Result<ExtractedExports, String> {
+        let tree = self
+            .parser
+            .parse(source, None)
+            .ok_or_else(|| "failed to parse file".to_string())?;
+        let root = tree.root_node();
+
+        let mut exports = ExtractedExports::default();
+        let public_names = dunder_all(root, source);
+        let is_public = |name: &str| match &public_names {
+            Some(names) => names.iter().any(|public| public == name),
+            None => !name.starts_with('_'),
+        };
+
+        let mut cursor = root.walk();
+        for statement in root.named_children(&mut cursor) {
+            if statement.has_error() {
+                continue;
+            }
+
+            let (definition, decorators) = match statement.kind() {
+                "decorated_definition" => match statement.child_by_field_name("definition") {
+                    Some(definition) => (definition, decorators(statement, source)),
+                    None => continue,
+                },
+                _ => (statement, Vec::new()),
+            };
+            let Some(name_node) = definition.child_by_field_name("name") else {
+                continue;
+            };
+            let name = text_for(name_node, source);
+            if !is_public(name) {
+                continue;
+            }
+
+            let line = name_node.start_position().row + 1;
+            let doc = docstring(definition, source);
+            match definition.kind() {
+                "function_definition" => exports.functions.push(ExtractedFunction {
+                    name: name.to_string(),
+                    signature: function_signature(definition, name, &decorators, source),
+                    line,
+                    doc,
+                    deprecated: None,
+                    overloads: Vec::new(),
+                    ambient: false,
+                    namespace: None,
+                }),
+                "class_definition" => exports.types.push(ExtractedType {
+                    name: name.to_string(),
+                    signature: class_signature(definition, name, &decorators, source),
+                    line,
+                    doc,
+                    deprecated: None,
+                    ambient: false,
+                    namespace: None,
+                }),
+                _ => {}
+            }
+        }
+
+        if root.has_error() {
+            collect_syntax_errors(root, source, &mut exports.syntax_errors);
+        }
+
+        exports.sort();
+        Ok(exports)
+    }
+}
+
+impl LanguageExtractor for PythonExportParser {
+    fn name(&self) -> &str {
+        "python"
+    }
+
+    fn handles(&self, path: &Path, _options: &WalkOptions) -> bool {
+        path.extension().is_some_and(|ext| ext == "py")
+    }
+
+    fn extract(&mut self, source: &str, _path: &Path) -> Result<ExtractedExports, String> {
+        self.extract_exports(source)
+    }
+}
+
+/// Names listed in `__all__ = [...]` (and `__all__ += [...]`), or `None` when
+/// the module does not declare it.
+fn dunder_all(root: Node<'_>, source: &str) -> Option<Vec<String>> {
+    let mut names: Option<Vec<String>> = None;
+    let mut cursor = root.walk();
+
+    for statement in root.named_children(&mut cursor) {
+        if statement.kind() != "expression_statement" {
+            continue;
+        }
+        let Some(assignment) = statement.named_child(0) else {
+            continue;
+        };
+        if !matches!(assignment.kind(), "assignment" | "augmented_assignment") {
+            continue;
+        }
+        let is_all = assignment
+            .child_by_field_name("left")
+            .is_some_and(|left| text_for(left, source) == "__all__");
+        let Some(right) = assignment.child_by_field_name("right") else {
+            continue;
+        };
+        if !is_all || !matches!(right.kind(), "list" | "tuple") {
+            continue;
+        }
+
+        let listed = names.get_or_insert_with(Vec::new);
+        if assignment.kind() == "assignment" {
+            listed.clear();
+        }
+        let mut items = right.walk();
+        listed.extend(
+            right
+                .named_children(&mut items)
+                .filter(|item| item.kind() == "string")
+                .filter_map(|item| string_content(item, source)),
+        );
+    }
+
+    names
+}
+
+fn decorators(node: Node<'_>, source: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|child| child.kind() == "decorator")
+        .map(|decorator| text_for(decorator, source).to_string())
+        .collect()
+}
+
+/// `@cache async fetch(url: str) -> bytes`
+fn function_signature(node: Node<'_>, name: &str, decorators: &[String], source: &str) -> String {
+    let parameters = node
+        .child_by_field_name("parameters")
+        .map(|n| text_for(n, source))
+        .unwrap_or("()");
+
+    let mut signature = decorators.join(" ");
+    if !signature.is_empty() {
+        signature.push(' ');
+    }
+    if text_for(node, source).starts_with("async") {
+        signature.push_str("async ");
+    }
+    signature.push_str(name);
+    signature.push_str(parameters);
+    if let Some(return_type) = node.child_by_field_name("return_type") {
+        signature.push_str(" -> ");
+        signature.push_str(text_for(return_type, source));
+    }
+    signature
+}
+
+/// `@dataclass(frozen=True) class Point(Base)`, or `None` for a bare
+/// `class Point`.
+fn class_signature(
+    node: Node<'_>,
+    name: &str,
+    decorators: &[String],
+    source: &str,
+) -> Option<String> {
+    let superclasses = node.child_by_field_name("superclasses");
+    if decorators.is_empty() && superclasses.is_none() {
+        return None;
+    }
+
+    let mut signature = decorators.join(" ");
+    if !signature.is_empty() {
+        signature.push(' ');
+    }
+    signature.push_str("class ");
+    signature.push_str(name);
+    if let Some(superclasses) = superclasses {
+        signature.push_str(text_for(superclasses, source));
+    }
+    Some(signature)
+}
+
+/// The first line of a function or class docstring becomes the doc summary.
+fn docstring(definition: Node<'_>, source: &str) -> Option<DocComment> {
+    let body = definition.child_by_field_name("body")?;
+    let first = body.named_child(0)?;
+    if first.kind() != "expression_statement" {
+        return None;
+    }
+    let string = first
+        .named_child(0)
+        .filter(|node| node.kind() == "string")?;
+    let summary = string_content(string, source)?
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?
+        .to_string();
+
+    Some(DocComment {
+        summary: Some(summary),
+        ..DocComment::default()
+    })
+}
+
+fn string_content(node: Node<'_>, source: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|child| child.kind() == "string_content")
+        .map(|child| text_for(child, source).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PythonExportParser;
+
+    #[test]
+    fn extracts_public_functions_and_classes() {
+        let mut parser = PythonExportParser::new().expect("parser");
+        let source = r#"import functools
+
+@functools.cache
+async def fetch(url: str, *, timeout: float = 1.0) -> bytes:
+    """Fetches a URL.
+
+    Longer text.
+    """
+    ...
+
+def _private():
+    pass
+
+class Client(Base):
+    'HTTP client.'
+
+def broken(:
+    pass
+"#;
+        let exports = parser.extract_exports(source).expect("extract");
+
+        assert_eq!(exports.functions.len(), 1);
+        let fetch = &exports.functions[0];
+        assert_eq!(fetch.name, "fetch");
+        assert_eq!(fetch.line, 4);
+        assert_eq!(
+            fetch.signature,
+            "@functools.cache async fetch(url: str, *, timeout: float = 1.0) -> bytes"
+        );
+        assert_eq!(
+            fetch.doc.as_ref().and_then(|doc| doc.summary.as_deref()),
+            Some("Fetches a URL.")
+        );
+        assert_eq!(exports.types[0].name, "Client");
+        assert_eq!(exports.types[0].signature.as_deref(), Some("class Client(Base)"));
+        assert_eq!(
+            exports.types[0]
+                .doc
+                .as_ref()
+                .and_then(|doc| doc.summary.as_deref()),
+            Some("HTTP client.")
+        );
+        assert!(!exports.syntax_errors.is_empty());
+    }
+
+    #[test]
+    fn keeps_class_decorators() {
+        let mut parser = PythonExportParser::new().expect("parser");
+        let source = r#"from dataclasses import dataclass
+
+@dataclass(frozen=True)
+class Point:
+    x: int
+    y: int
+
+class Plain:
+    pass
+"#;
+        let exports = parser.extract_exports(source).expect("extract");
+
+        let signatures = exports
+            .types
+            .iter()
+            .map(|t| (t.name.as_str(), t.signature.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            signatures,
+            vec![
+                ("Point", Some("@dataclass(frozen=True) class Point")),
+                ("Plain", None)
+            ]
+        );
+        assert_eq!(exports.types[0].line, 4);
+    }
+
+    #[test]
+    fn respects_dunder_all() {
+        let mut parser = PythonExportParser::new().expect("parser");
+        let source = r#"__all__ = ["visible", "_Exported"]
+__all__ += ("Extra",)
+
+def visible(): ...
+def hidden(): ...
+class _Exported: ...
+class Extra: ...
+"#;
+        let exports = parser.extract_exports(source).expect("extract");
+
+        let functions = exports
+            .functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        let types = exports
+            .types
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(functions, vec!["visible"]);
+        assert_eq!(types, vec!["_Exported", "Extra"]);
+    }
+}
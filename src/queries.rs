@@ -0,0 +1,240 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use tree_sitter::{Language, Node, Query, QueryCursor};
+
+use crate::parser::{ExtractedCustom, text_for};
+
+/// Where user queries live, relative to the scanned root.
+pub const QUERY_DIR: &str = ".context-map/queries";
+
+/// A `.scm` query file. Each match must capture `@name`; `@signature` and
+/// `@kind` are optional. Matches are listed under `title`, taken from a
+/// `; title: ...` line or else the file stem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserQuery {
+    pub title: String,
+    pub path: PathBuf,
+    pub source: String,
+}
+
+/// Reads every `.scm` file in [`QUERY_DIR`], sorted by file name. A missing
+/// directory means no queries.
+pub fn load_user_queries(root: &Path) -> io::Result<Vec<UserQuery>> {
+    let dir = root.join(QUERY_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = fs::read_dir(&dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "scm"));
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let source = fs::read_to_string(&path)?;
+            Ok(UserQuery {
+                title: query_title(&path, &source),
+                path,
+                source,
+            })
+        })
+        .collect()
+}
+
+fn query_title(path: &Path, source: &str) -> String {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.starts_with(';') || line.is_empty())
+        .find_map(|line| {
+            let comment = line.trim_start_matches(';').trim();
+            comment
+                .strip_prefix("title:")
+                .map(|title| title.trim().to_string())
+        })
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+}
+
+/// User queries compiled for one grammar.
+#[derive(Default)]
+pub(crate) struct QuerySet {
+    queries: Vec<(String, Query)>,
+}
+
+impl QuerySet {
+    /// One set per named grammar, each holding the queries that compile for
+    /// it, so a JSX-only query still runs on TSX and JavaScript files. Fails
+    /// on a query that compiles for none of the grammars.
+    pub(crate) fn compile_each<const N: usize>(
+        queries: &[UserQuery],
+        grammars: [(&str, &Language); N],
+    ) -> Result<[Self; N], String> {
+        let mut sets = grammars.map(|_| Self::default());
+        for query in queries {
+            let mut errors = Vec::new();
+            for (set, (grammar, language)) in sets.iter_mut().zip(grammars) {
+                match Query::new(language, &query.source) {
+                    Ok(compiled) => set.queries.push((query.title.clone(), compiled)),
+                    Err(err) => errors.push(format!("{grammar}: {err}")),
+                }
+            }
+            if errors.len() == N {
+                return Err(format!(
+                    "invalid query {}: {}",
+                    query.path.display(),
+                    errors.join("; ")
+                ));
+            }
+        }
+        Ok(sets)
+    }
+
+    pub(crate) fn run(&self, root: Node<'_>, source: &str) -> Vec<ExtractedCustom> {
+        let mut out = Vec::new();
+        let mut cursor = QueryCursor::new();
+
+        for (title, query) in &self.queries {
+            let capture = |name| query.capture_index_for_name(name);
+            let (Some(name_index), signature_index, kind_index) =
+                (capture("name"), capture("signature"), capture("kind"))
+            else {
+                continue;
+            };
+
+            for query_match in cursor.matches(query, root, source.as_bytes()) {
+                let node_for = |index: Option<u32>| {
+                    index.and_then(|index| {
+                        query_match
+                            .captures
+                            .iter()
+                            .find(|capture| capture.index == index)
+                            .map(|capture| capture.node)
+                    })
+                };
+                let Some(name) = node_for(Some(name_index)) else {
+                    continue;
+                };
+
+                out.push(ExtractedCustom {
+                    section: title.clone(),
+                    name: text_for(name, source).to_string(),
+                    signature: node_for(signature_index).map(|n| text_for(n, source).to_string()),
+                    kind: node_for(kind_index).map(|n| text_for(n, source).to_string()),
+                    line: name.start_position().row + 1,
+                });
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::{QUERY_DIR, QuerySet, load_user_queries};
+
+    #[test]
+    fn loads_titles_and_runs_captures() {
+        let temp = TempDir::new().expect("temp dir");
+        let dir = temp.path().join(QUERY_DIR);
+        fs::create_dir_all(&dir).expect("mkdir");
+        fs::write(
+            dir.join("nitro.scm"),
+            r#"; title: Nitro Routes
+(export_statement
+  (lexical_declaration
+    (variable_declarator
+      name: (identifier) @name
+      value: (call_expression function: (identifier) @kind) @signature))
+  (#eq? @kind "defineEventHandler"))
+"#,
+        )
+        .expect("write query");
+        fs::write(dir.join("slices.scm"), "(call_expression) @signature\n").expect("write query");
+        fs::write(dir.join("notes.txt"), "ignored").expect("write other");
+
+        let queries = load_user_queries(temp.path()).expect("load");
+        let titles = queries.iter().map(|q| q.title.as_str()).collect::<Vec<_>>();
+        assert_eq!(titles, vec!["Nitro Routes", "slices"]);
+
+        let language = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        let [set] = QuerySet::compile_each(&queries, [("TypeScript", &language)]).expect("compile");
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&language).expect("language");
+        let source = "\nexport const users = defineEventHandler(() => []);\nexport const other = wrap(() => 1);\n";
+        let tree = parser.parse(source, None).expect("parse");
+
+        let found = set.run(tree.root_node(), source);
+
+        assert_eq!(found.len(), 1, "queries without @name are skipped");
+        assert_eq!(found[0].section, "Nitro Routes");
+        assert_eq!(found[0].name, "users");
+        assert_eq!(found[0].kind.as_deref(), Some("defineEventHandler"));
+        assert_eq!(found[0].line, 2);
+        assert_eq!(
+            found[0].signature.as_deref(),
+            Some("defineEventHandler(() => [])")
+        );
+    }
+
+    #[test]
+    fn reports_queries_that_do_not_compile() {
+        let temp = TempDir::new().expect("temp dir");
+        let dir = temp.path().join(QUERY_DIR);
+        fs::create_dir_all(&dir).expect("mkdir");
+        fs::write(dir.join("bad.scm"), "(no_such_node) @name\n").expect("write query");
+
+        let queries = load_user_queries(temp.path()).expect("load");
+        let ts = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        let js = tree_sitter_javascript::LANGUAGE.into();
+        let err = QuerySet::compile_each(&queries, [("TypeScript", &ts), ("JavaScript", &js)])
+            .err()
+            .expect("invalid query");
+
+        assert!(err.starts_with("invalid query "));
+        assert!(err.contains("bad.scm"));
+        assert!(err.contains("TypeScript: ") && err.contains("JavaScript: "));
+    }
+
+    #[test]
+    fn keeps_queries_that_fit_only_some_grammars() {
+        let temp = TempDir::new().expect("temp dir");
+        let dir = temp.path().join(QUERY_DIR);
+        fs::create_dir_all(&dir).expect("mkdir");
+        fs::write(
+            dir.join("components.scm"),
+            "(jsx_self_closing_element name: (identifier) @name)\n",
+        )
+        .expect("write query");
+
+        let queries = load_user_queries(temp.path()).expect("load");
+        let ts = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        let tsx = tree_sitter_typescript::LANGUAGE_TSX.into();
+        let [ts_set, tsx_set] =
+            QuerySet::compile_each(&queries, [("TypeScript", &ts), ("TSX", &tsx)])
+                .expect("compiles for TSX");
+        assert!(ts_set.queries.is_empty());
+
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&tsx).expect("language");
+        let source = "export const App = () => <Header />;\n";
+        let tree = parser.parse(source, None).expect("parse");
+        let found = tsx_set.run(tree.root_node(), source);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "Header");
+    }
+}
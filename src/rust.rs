@@ -0,0 +1,415 @@
+use std::path::{Component, Path};
+
+use tree_sitter::{Node, Parser};
+
+use crate::docs::DocComment;
+use crate::language::LanguageExtractor;
+use crate::parser::{
+    ExtractedExports, ExtractedFunction, ExtractedType, collect_syntax_errors, text_for,
+};
+use crate::walker::WalkOptions;
+
+/// Indexes the `pub` items of a Rust source file. Items are qualified with
+/// their module path (`handlers::user::create`), built from the file's place
+/// under `src/` plus any inline `pub mod` blocks.
+pub struct RustExportParser {
+    parser: Parser,
+}
+
+impl RustExportParser {
+    pub fn new() -> Result<Self, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .map_err(|err| format!("{err}"))?;
+
+        Ok(Self { parser })
+    }
+
+    pub fn extract_exports(
+        &mut self,
+        source: &str,
+        module_path: &[String],
+    ) -> Result<ExtractedExports, String> {
+        let tree = self
+            .parser
+            .parse(source, None)
+            .ok_or_else(|| "failed to parse file".to_string())?;
+        let root = tree.root_node();
+
+        let mut exports = ExtractedExports::default();
+        extract_items(root, source, module_path, &mut exports);
+        if root.has_error() {
+            collect_syntax_errors(root, source, &mut exports.syntax_errors);
+        }
+
+        exports.sort();
+        Ok(exports)
+    }
+}
+
+impl LanguageExtractor for RustExportParser {
+    fn name(&self) -> &str {
+        "rust"
+    }
+
+    fn handles(&self, path: &Path, _options: &WalkOptions) -> bool {
+        path.extension().is_some_and(|ext| ext == "rs")
+    }
+
+    fn extract(&mut self, source: &str, path: &Path) -> Result<ExtractedExports, String> {
+        self.extract_exports(source, &module_path_from_file(path))
+    }
+}
+
+/// Module path implied by where a file sits under its crate's `src/`:
+/// `src/lib.rs` is the crate root, `src/a/mod.rs` and `src/a.rs` are `a`,
+/// `src/bin/tool.rs` is the root of its own binary crate. `path` is relative
+/// to the scanned root; the first `src` in it is the crate's source directory,
+/// so deeper directories named `src` stay modules.
+pub fn module_path_from_file(path: &Path) -> Vec<String> {
+    let components = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let Some(src) = components.iter().position(|part| *part == "src") else {
+        return Vec::new();
+    };
+
+    let mut segments = components[src + 1..]
+        .iter()
+        .map(|part| part.strip_suffix(".rs").unwrap_or(part).to_string())
+        .collect::<Vec<_>>();
+    if segments
+        .last()
+        .is_some_and(|last| matches!(last.as_str(), "lib" | "main" | "mod"))
+    {
+        segments.pop();
+    }
+    if segments.first().is_some_and(|first| first == "bin") {
+        segments.drain(..segments.len().min(2));
+    }
+    segments
+}
+
+fn extract_items(
+    block: Node<'_>,
+    source: &str,
+    module_path: &[String],
+    out: &mut ExtractedExports,
+) {
+    let mut cursor = block.walk();
+    for item in block.named_children(&mut cursor) {
+        if item.has_error() {
+            continue;
+        }
+
+        match item.kind() {
+            "function_item" if is_pub(item, source) => {
+                out.functions
+                    .extend(function_export(item, source, module_path));
+            }
+            "struct_item" | "enum_item" | "type_item" if is_pub(item, source) => {
+                out.types.extend(type_export(item, source, module_path));
+            }
+            "trait_item" if is_pub(item, source) => {
+                let Some(trait_export) = type_export(item, source, module_path) else {
+                    continue;
+                };
+                // Trait methods are public wherever the trait is.
+                let trait_path = qualified_path(module_path, &trait_export.name);
+                out.functions
+                    .extend(member_functions(item, source, &trait_path, false));
+                out.types.push(trait_export);
+            }
+            "impl_item" if item.child_by_field_name("trait").is_none() => {
+                let Some(self_type) = item.child_by_field_name("type") else {
+                    continue;
+                };
+                let type_name = base_type_name(self_type, source);
+                let type_path = qualified_path(module_path, type_name);
+                out.functions
+                    .extend(member_functions(item, source, &type_path, true));
+            }
+            "mod_item" if is_pub(item, source) => {
+                let (Some(name), Some(body)) = (
+                    item.child_by_field_name("name"),
+                    item.child_by_field_name("body"),
+                ) else {
+                    continue;
+                };
+                let inner_path = qualified_path(module_path, text_for(name, source));
+                extract_items(body, source, &inner_path, out);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Methods of a trait or inherent impl; impls only list their `pub fn`s.
+fn member_functions(
+    item: Node<'_>,
+    source: &str,
+    owner_path: &[String],
+    require_pub: bool,
+) -> Vec<ExtractedFunction> {
+    let Some(body) = item.child_by_field_name("body") else {
+        return Vec::new();
+    };
+
+    let mut cursor = body.walk();
+    body.named_children(&mut cursor)
+        .filter(|member| matches!(member.kind(), "function_item" | "function_signature_item"))
+        .filter(|member| !require_pub || is_pub(*member, source))
+        .filter_map(|member| function_export(member, source, owner_path))
+        .collect()
+}
+
+/// Only plain `pub` counts; `pub(crate)`, `pub(super)` and `pub(in ...)` do not.
+fn is_pub(item: Node<'_>, source: &str) -> bool {
+    let mut cursor = item.walk();
+    item.children(&mut cursor)
+        .find(|child| child.kind() == "visibility_modifier")
+        .is_some_and(|visibility| text_for(visibility, source) == "pub")
+}
+
+fn function_export(
+    node: Node<'_>,
+    source: &str,
+    module_path: &[String],
+) -> Option<ExtractedFunction> {
+    let name_node = node.child_by_field_name("name")?;
+    let name = qualified_name(module_path, text_for(name_node, source));
+    let type_parameters = node
+        .child_by_field_name("type_parameters")
+        .map(|n| text_for(n, source))
+        .unwrap_or_default();
+    let parameters = node
+        .child_by_field_name("parameters")
+        .map(|n| text_for(n, source))
+        .unwrap_or("()");
+
+    let mut signature = format!("{name}{type_parameters}{parameters}");
+    if let Some(return_type) = node.child_by_field_name("return_type") {
+        signature.push_str(" -> ");
+        signature.push_str(text_for(return_type, source));
+    }
+
+    Some(ExtractedFunction {
+        name,
+        signature,
+        line: name_node.start_position().row + 1,
+        doc: doc_comment(node, source),
+        deprecated: deprecated_attribute(node, source),
+        overloads: Vec::new(),
+        ambient: false,
+        namespace: namespace_for(module_path),
+    })
+}
+
+fn type_export(node: Node<'_>, source: &str, module_path: &[String]) -> Option<ExtractedType> {
+    let name_node = node.child_by_field_name("name")?;
+    Some(ExtractedType {
+        name: qualified_name(module_path, text_for(name_node, source)),
+        signature: None,
+        line: name_node.start_position().row + 1,
+        doc: doc_comment(node, source),
+        deprecated: deprecated_attribute(node, source),
+        ambient: false,
+        namespace: namespace_for(module_path),
+    })
+}
+
+/// `Wrapper<T>` and `crate::a::Wrapper` both resolve to `Wrapper`.
+fn base_type_name<'a>(node: Node<'_>, source: &'a str) -> &'a str {
+    let target = match node.kind() {
+        "generic_type" => node.child_by_field_name("type").unwrap_or(node),
+        _ => node,
+    };
+    let text = text_for(target, source);
+    text.rsplit("::").next().unwrap_or(text)
+}
+
+/// The `///` lines (or `/** */` block) above an item, looking past attributes.
+fn doc_comment(node: Node<'_>, source: &str) -> Option<DocComment> {
+    let mut lines = Vec::new();
+    let mut current = node.prev_sibling();
+
+    while let Some(sibling) = current {
+        match sibling.kind() {
+            "attribute_item" => {}
+            "line_comment" => {
+                let text = text_for(sibling, source);
+                match text.strip_prefix("///") {
+                    Some(line) if !line.starts_with('/') => lines.push(line.trim().to_string()),
+                    _ => break,
+                }
+            }
+            "block_comment" => {
+                let raw = text_for(sibling, source);
+                if raw.starts_with("/**") {
+                    return crate::docs::parse_doc_comment(raw);
+                }
+                break;
+            }
+            _ => break,
+        }
+        current = sibling.prev_sibling();
+    }
+
+    lines.reverse();
+    let summary = lines
+        .iter()
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!summary.is_empty()).then(|| DocComment {
+        summary: Some(summary),
+        ..DocComment::default()
+    })
+}
+
+/// `#[deprecated]` or `#[deprecated(note = "...")]` / `#[deprecated = "..."]`.
+fn deprecated_attribute(node: Node<'_>, source: &str) -> Option<String> {
+    let mut current = node.prev_sibling();
+
+    while let Some(sibling) = current {
+        match sibling.kind() {
+            "attribute_item" => {
+                let text = text_for(sibling, source);
+                let inner = text.trim_start_matches("#[").trim_end_matches(']').trim();
+                if let Some(rest) = inner.strip_prefix("deprecated") {
+                    let note = rest
+                        .split_once('"')
+                        .and_then(|(_, tail)| tail.rsplit_once('"'))
+                        .map(|(note, _)| note.to_string());
+                    return Some(note.unwrap_or_default());
+                }
+            }
+            "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        current = sibling.prev_sibling();
+    }
+
+    None
+}
+
+fn qualified_path(module_path: &[String], name: &str) -> Vec<String> {
+    let mut path = module_path.to_vec();
+    path.push(name.rsplit("::").next().unwrap_or(name).to_string());
+    path
+}
+
+fn qualified_name(module_path: &[String], name: &str) -> String {
+    qualified_path(module_path, name).join("::")
+}
+
+fn namespace_for(module_path: &[String]) -> Option<String> {
+    (!module_path.is_empty()).then(|| module_path.join("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{RustExportParser, module_path_from_file};
+
+    #[test]
+    fn extracts_pub_items_with_module_paths() {
+        let mut parser = RustExportParser::new().expect("parser");
+        let source = r#"/// Creates a user.
+///
+/// Longer text.
+#[inline]
+pub fn create<T: Into<String>>(name: T) -> User { todo!() }
+pub(crate) fn internal() {}
+fn private() {}
+
+pub struct User { id: u64 }
+pub enum Role { Admin }
+pub type Id = u64;
+
+pub trait Store {
+    fn save(&self, user: &User) -> bool;
+}
+
+impl User {
+    pub fn id(&self) -> u64 { self.id }
+    fn secret(&self) {}
+}
+
+impl Store for User {
+    fn save(&self, _user: &User) -> bool { true }
+}
+
+pub mod admin {
+    #[deprecated(note = "use grant_role")]
+    pub fn promote() {}
+}
+
+mod hidden {
+    pub fn unreachable() {}
+}
+"#;
+        let module = vec!["users".to_string()];
+        let exports = parser.extract_exports(source, &module).expect("extract");
+
+        let functions = exports
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.namespace.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            functions,
+            vec![
+                ("users::create", Some("users")),
+                ("users::Store::save", Some("users::Store")),
+                ("users::User::id", Some("users::User")),
+                ("users::admin::promote", Some("users::admin")),
+            ]
+        );
+        let create = &exports.functions[0];
+        assert_eq!(
+            create.signature,
+            "users::create<T: Into<String>>(name: T) -> User"
+        );
+        assert_eq!(create.line, 5);
+        assert_eq!(
+            create.doc.as_ref().and_then(|doc| doc.summary.as_deref()),
+            Some("Creates a user.")
+        );
+        assert_eq!(
+            exports.functions[3].deprecated.as_deref(),
+            Some("use grant_role")
+        );
+
+        let types = exports
+            .types
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec!["users::User", "users::Role", "users::Id", "users::Store"]
+        );
+    }
+
+    #[test]
+    fn derives_module_path_from_file_layout() {
+        let path = |p: &str| module_path_from_file(Path::new(p)).join("::");
+
+        assert_eq!(path("src/lib.rs"), "");
+        assert_eq!(path("src/handlers/mod.rs"), "handlers");
+        assert_eq!(path("crates/api/src/handlers/user.rs"), "handlers::user");
+        assert_eq!(path("src/codegen/src/emit.rs"), "codegen::src::emit");
+        assert_eq!(path("src/bin/tool.rs"), "");
+        assert_eq!(path("src/bin/tool/args.rs"), "args");
+        assert_eq!(path("build.rs"), "");
+    }
+}
@@ -0,0 +1,430 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::markdown::{counted, is_within, render_markdown_with_config};
+use crate::{ContextMapError, FileResult, RenderConfig, RunOutput, RunSummary, SplitMode};
+
+/// File written into each split directory.
+pub const PART_FILE_NAME: &str = "CONTEXT.md";
+
+/// First line of every part file; only files starting with it are overwritten
+/// or deleted as stale.
+const GENERATED_MARKER: &str = "<!-- generated by context-map; do not edit -->";
+
+/// Opens the block at the end of the index that lists the part files it owns.
+const PARTS_START: &str = "<!-- context-map parts";
+
+/// Files whose presence makes a directory a package for [`SplitMode::Package`].
+const PACKAGE_MANIFESTS: [&str; 5] = [
+    "package.json",
+    "Cargo.toml",
+    "go.mod",
+    "pyproject.toml",
+    "setup.py",
+];
+
+/// One directory's slice of a run, with paths relative to `dir`.
+#[derive(Debug, Clone)]
+pub struct SplitPart {
+    pub dir: String,
+    pub output: RunOutput,
+}
+
+/// Divides `output` into the parts `mode` asks for and what stays in the
+/// root index. Every file goes to the deepest part directory containing it.
+pub fn split_output(output: &RunOutput, mode: SplitMode) -> (RunOutput, Vec<SplitPart>) {
+    let dirs = part_dirs(output, mode);
+    let part_of = |path: &str| {
+        dirs.iter()
+            .filter(|dir| is_within(path, dir))
+            .max_by_key(|dir| dir.len())
+            .map(String::as_str)
+    };
+
+    let index = subset(output, None, &part_of);
+    let parts = dirs
+        .iter()
+        .map(|dir| SplitPart {
+            dir: dir.clone(),
+            output: subset(output, Some(dir), &part_of),
+        })
+        .collect();
+    (index, parts)
+}
+
+fn part_dirs(output: &RunOutput, mode: SplitMode) -> Vec<String> {
+    let root = Path::new(&output.root_path);
+    let mut dirs = BTreeSet::new();
+    for file in &output.file_results {
+        match mode {
+            SplitMode::Single => {}
+            SplitMode::Directory => {
+                if let Some((dir, _)) = file.file_path.split_once('/') {
+                    dirs.insert(dir.to_string());
+                }
+            }
+            SplitMode::Package => {
+                let mut path = file.file_path.as_str();
+                while let Some((dir, _)) = path.rsplit_once('/') {
+                    if PACKAGE_MANIFESTS
+                        .iter()
+                        .any(|manifest| root.join(dir).join(manifest).is_file())
+                    {
+                        dirs.insert(dir.to_string());
+                        break;
+                    }
+                    path = dir;
+                }
+            }
+        }
+    }
+    dirs.into_iter().collect()
+}
+
+/// The entries, files and walk errors that belong to `dir` (the index when
+/// `None`), rebased onto it.
+fn subset<'a>(
+    output: &RunOutput,
+    dir: Option<&str>,
+    part_of: &impl Fn(&str) -> Option<&'a str>,
+) -> RunOutput {
+    let rebase = |path: &str| match dir {
+        Some(dir) => path[dir.len() + 1..].to_string(),
+        None => path.to_string(),
+    };
+    let dir_depth = dir.map_or(0, |dir| dir.split('/').count());
+
+    let repo_entries = output
+        .repo_entries
+        .iter()
+        .filter(|entry| match dir {
+            Some(dir) => is_within(&entry.path, dir),
+            // Part directories stay in the index tree as leaves.
+            None => part_of(&entry.path).is_none(),
+        })
+        .map(|entry| crate::RepoEntry {
+            path: rebase(&entry.path),
+            is_dir: entry.is_dir,
+            depth: entry.depth - dir_depth,
+        })
+        .collect();
+
+    let file_results = output
+        .file_results
+        .iter()
+        .filter(|file| part_of(&file.file_path) == dir)
+        .map(|file| {
+            let mut file = file.clone();
+            file.file_path = rebase(&file.file_path);
+            for export in &mut file.function_exports {
+                export.file_path = file.file_path.clone();
+            }
+            for export in &mut file.type_exports {
+                export.file_path = file.file_path.clone();
+            }
+            for prop in &mut file.component_props {
+                prop.file_path = file.file_path.clone();
+            }
+            for custom in &mut file.custom_exports {
+                custom.file_path = file.file_path.clone();
+            }
+            file
+        })
+        .collect::<Vec<_>>();
+
+    let walk_errors = output
+        .walk_errors
+        .iter()
+        .filter(|err| part_of(&err.path) == dir)
+        .map(|err| crate::WalkError {
+            path: rebase(&err.path),
+            message: err.message.clone(),
+        })
+        .collect();
+
+    let root_path = match dir {
+        Some(dir) => Path::new(&output.root_path).join(dir).display().to_string(),
+        None => output.root_path.clone(),
+    };
+    RunOutput {
+        root_path,
+        repo_entries,
+        summary: summarize(&file_results),
+        file_results,
+        walk_errors,
+    }
+}
+
+fn summarize(files: &[FileResult]) -> RunSummary {
+    let parse_failed = files.iter().filter(|f| f.parse_error.is_some()).count();
+    RunSummary {
+        scanned: files.len(),
+        parsed: files.len() - parse_failed,
+        parse_failed,
+        exported_functions: files.iter().map(|f| f.function_exports.len()).sum(),
+        exported_types: files.iter().map(|f| f.type_exports.len()).sum(),
+    }
+}
+
+/// Writes the index to `out` and a [`PART_FILE_NAME`] per part, then deletes
+/// part files a previous run listed that this one no longer produces.
+pub(crate) fn write_split(
+    out: &Path,
+    output: &RunOutput,
+    config: RenderConfig,
+) -> Result<(), ContextMapError> {
+    let root = Path::new(&output.root_path);
+    let (index, parts) = split_output(output, config.split);
+    let previous = previous_parts(out);
+
+    let mut written = Vec::new();
+    let mut links = vec!["# Context Files".to_string()];
+    let out_dir = out
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    // Check every part before writing any, so a refusal leaves the tree as it was.
+    for part in &parts {
+        let path = root.join(&part.dir).join(PART_FILE_NAME);
+        if path.exists() && !is_generated(&path) {
+            return Err(ContextMapError::Write {
+                path,
+                source: io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "file was not generated by context-map; refusing to overwrite it",
+                ),
+            });
+        }
+    }
+    for part in &parts {
+        let relative = format!("{}/{PART_FILE_NAME}", part.dir);
+        let path = root.join(&relative);
+        let rendered = render_markdown_with_config(&part.output, config);
+        fs::write(&path, format!("{GENERATED_MARKER}\n{rendered}")).map_err(|source| {
+            ContextMapError::Write {
+                path: path.clone(),
+                source,
+            }
+        })?;
+
+        let summary = &part.output.summary;
+        links.push(format!(
+            "- [`{}/`]({}) — {}, {}, {}",
+            part.dir,
+            relative_link(out_dir, &path),
+            counted(summary.scanned, "file", "files"),
+            counted(summary.exported_functions, "function", "functions"),
+            counted(summary.exported_types, "type", "types")
+        ));
+        written.push(relative);
+    }
+    if parts.is_empty() {
+        links.push("No directories to split.".to_string());
+    }
+
+    let mut rendered = links.join("\n") + "\n\n" + &render_markdown_with_config(&index, config);
+    rendered.push_str(&format!("\n{PARTS_START}\n"));
+    for relative in &written {
+        rendered.push_str(relative);
+        rendered.push('\n');
+    }
+    rendered.push_str("-->\n");
+    fs::write(out, rendered).map_err(|source| ContextMapError::Write {
+        path: out.to_path_buf(),
+        source,
+    })?;
+
+    remove_stale(root, &previous, &written)
+}
+
+/// Deletes the part files an earlier split run recorded in `out`. Used when
+/// writing a single file so switching modes leaves no orphans behind.
+pub(crate) fn remove_previous_parts(out: &Path, root: &Path) -> Result<(), ContextMapError> {
+    remove_stale(root, &previous_parts(out), &[])
+}
+
+/// Part files listed at the end of an earlier index at `out`.
+fn previous_parts(out: &Path) -> Vec<String> {
+    let Ok(existing) = fs::read_to_string(out) else {
+        return Vec::new();
+    };
+    let Some((_, listed)) = existing.rsplit_once(&format!("{PARTS_START}\n")) else {
+        return Vec::new();
+    };
+    listed
+        .lines()
+        .take_while(|line| *line != "-->")
+        .map(str::to_string)
+        .collect()
+}
+
+fn remove_stale(
+    root: &Path,
+    previous: &[String],
+    written: &[String],
+) -> Result<(), ContextMapError> {
+    for relative in previous.iter().filter(|path| !written.contains(path)) {
+        // Never follow a listed path out of the root.
+        if Path::new(relative)
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            continue;
+        }
+        let path = root.join(relative);
+        if is_generated(&path) {
+            fs::remove_file(&path).map_err(|source| ContextMapError::Io { path, source })?;
+        }
+    }
+    Ok(())
+}
+
+fn is_generated(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.lines().next() == Some(GENERATED_MARKER))
+}
+
+/// `to` relative to the directory `from`, with `/` separators. Falls back to
+/// the absolute path when either side cannot be resolved.
+fn relative_link(from: &Path, to: &Path) -> String {
+    let (Ok(from), Ok(to)) = (fs::canonicalize(from), fs::canonicalize(to)) else {
+        return crate::normalize_path(to);
+    };
+    let from = from.components().collect::<Vec<_>>();
+    let to = to.components().collect::<Vec<_>>();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut link = PathBuf::new();
+    for _ in common..from.len() {
+        link.push("..");
+    }
+    for component in &to[common..] {
+        link.push(component);
+    }
+    crate::normalize_path(&link)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::{PART_FILE_NAME, split_output, write_split};
+    use crate::{ContextMapError, RenderConfig, SplitMode};
+
+    #[test]
+    fn splits_by_package_and_rebases_paths() {
+        let temp = TempDir::new().expect("temp dir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("packages/api/src")).expect("mkdir api");
+        fs::create_dir_all(root.join("scripts")).expect("mkdir scripts");
+        fs::write(root.join("packages/api/package.json"), "{}").expect("manifest");
+        fs::write(
+            root.join("packages/api/src/server.ts"),
+            "export function serve() {}\n",
+        )
+        .expect("write server");
+        fs::write(
+            root.join("scripts/build.ts"),
+            "export function build() {}\n",
+        )
+        .expect("write build");
+
+        let output = crate::generate_context_map(root).expect("generate");
+        let (index, parts) = split_output(&output, SplitMode::Package);
+
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].dir, "packages/api");
+        let part = &parts[0].output;
+        assert_eq!(part.file_results[0].file_path, "src/server.ts");
+        assert_eq!(
+            part.file_results[0].function_exports[0].file_path,
+            "src/server.ts"
+        );
+        assert!(
+            part.repo_entries
+                .iter()
+                .any(|e| e.path == "src" && e.depth == 1)
+        );
+        assert_eq!(part.summary.exported_functions, 1);
+
+        let index_files = index
+            .file_results
+            .iter()
+            .map(|f| f.file_path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(index_files, vec!["scripts/build.ts"]);
+        assert!(index.repo_entries.iter().any(|e| e.path == "packages/api"));
+        assert!(
+            !index
+                .repo_entries
+                .iter()
+                .any(|e| e.path == "packages/api/src")
+        );
+    }
+
+    #[test]
+    fn writes_parts_and_removes_stale_ones() {
+        let temp = TempDir::new().expect("temp dir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("web")).expect("mkdir web");
+        fs::create_dir_all(root.join("api")).expect("mkdir api");
+        fs::write(root.join("web/app.ts"), "export function app() {}\n").expect("write app");
+        fs::write(root.join("api/handler.ts"), "export function handle() {}\n").expect("write api");
+        let out = root.join("REPO.md");
+        let config = RenderConfig {
+            split: SplitMode::Directory,
+            ..RenderConfig::default()
+        };
+
+        let output = crate::generate_context_map(root).expect("generate");
+        write_split(&out, &output, config).expect("write split");
+
+        let index = fs::read_to_string(&out).expect("index");
+        assert!(index.starts_with(
+            "# Context Files\n- [`api/`](api/CONTEXT.md) — 1 file, 1 function, 0 types\n"
+        ));
+        assert!(index.contains("- [`web/`](web/CONTEXT.md)"));
+        let part = fs::read_to_string(root.join("web").join(PART_FILE_NAME)).expect("part");
+        assert!(part.contains("### `app.ts`\n- `app()`"));
+
+        fs::remove_file(root.join("api/handler.ts")).expect("remove handler");
+        fs::write(root.join("api/notes.md"), "hand written\n").expect("write notes");
+        let output = crate::generate_context_map(root).expect("generate again");
+        write_split(&out, &output, config).expect("write split again");
+
+        assert!(!root.join("api").join(PART_FILE_NAME).exists());
+        assert!(root.join("web").join(PART_FILE_NAME).exists());
+        assert!(root.join("api/notes.md").exists());
+    }
+
+    #[test]
+    fn refuses_to_overwrite_hand_written_part_files() {
+        let temp = TempDir::new().expect("temp dir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("api")).expect("mkdir api");
+        fs::create_dir_all(root.join("web")).expect("mkdir web");
+        fs::write(root.join("api/handler.ts"), "export function handle() {}\n").expect("write api");
+        fs::write(root.join("web/app.ts"), "export function app() {}\n").expect("write app");
+        fs::write(root.join("web").join(PART_FILE_NAME), "# Web notes\n").expect("write notes");
+        let out = root.join("REPO.md");
+        let config = RenderConfig {
+            split: SplitMode::Directory,
+            ..RenderConfig::default()
+        };
+
+        let output = crate::generate_context_map(root).expect("generate");
+        let err = write_split(&out, &output, config).expect_err("hand-written part");
+
+        assert!(matches!(err, ContextMapError::Write { .. }));
+        assert!(err.to_string().contains("web/CONTEXT.md"), "{err}");
+        assert_eq!(
+            fs::read_to_string(root.join("web").join(PART_FILE_NAME)).expect("notes"),
+            "# Web notes\n"
+        );
+        assert!(!root.join("api").join(PART_FILE_NAME).exists());
+        assert!(!out.exists());
+    }
+}
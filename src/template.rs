@@ -0,0 +1,382 @@
+use std::sync::Arc;
+
+use minijinja::value::Value;
+use minijinja::{Environment, Error, ErrorKind, context};
+
+use crate::markdown::{
+    custom_line, function_line, prop_entry, render_section_text, render_tree_text,
+    section_groups, section_query_titles, type_line,
+};
+use crate::{Grouping, RenderConfig, RenderProfile, RunOutput, Section};
+
+/// The built-in Markdown layout, written out section by section. Rendering it
+/// matches `render_markdown_with_config` without a token budget, which makes
+/// it the starting point for a custom `--template`.
+pub const DEFAULT_TEMPLATE: &str = include_str!("default_template.md.jinja");
+
+const SECTION_NAMES: [(&str, Section); 8] = [
+    ("summary", Section::Summary),
+    ("tree", Section::Tree),
+    ("functions", Section::Functions),
+    ("types", Section::Types),
+    ("props", Section::Props),
+    ("queries", Section::Queries),
+    ("deprecated", Section::Deprecated),
+    ("errors", Section::Errors),
+];
+
+const PROFILE_NAMES: [(&str, RenderProfile); 3] = [
+    ("compact", RenderProfile::Compact),
+    ("balanced", RenderProfile::Balanced),
+    ("detailed", RenderProfile::Detailed),
+];
+
+const GROUPING_NAMES: [(&str, Grouping); 3] = [
+    ("file", Grouping::File),
+    ("directory", Grouping::Directory),
+    ("symbol", Grouping::Symbol),
+];
+
+/// Renders `template` (Jinja syntax) with the run model as context: the
+/// [`RunOutput`] fields, `profile`, the configured `sections` names, the
+/// `query_titles` of user query sections and `config` (`include_types`,
+/// `hide_internal`, `hide_deprecated`, `grouping`). Helpers:
+/// - `signature(export, profile?)`: an export's Markdown entry line as the
+///   built-in sections render it, for any function, type, prop or query match
+/// - `tree()`: the repository tree lines, honouring the tree options
+/// - `groups(section, title?)`: a section's visible exports laid out for the
+///   grouping, as `heading` (none when grouping by symbol), `lines` and an
+///   `entries` count; `title` picks a query section
+/// - `section(name)`, also a filter: one built-in section, empty when it has
+///   nothing to show
+pub fn render_template(
+    output: &RunOutput,
+    config: RenderConfig,
+    template: &str,
+) -> Result<String, String> {
+    let shared = Arc::new(output.clone());
+    let mut env = Environment::new();
+    env.set_keep_trailing_newline(true);
+
+    let run = Arc::clone(&shared);
+    env.add_function(
+        "signature",
+        move |export: Value, profile: Option<String>| -> Result<String, Error> {
+            let profile = match profile {
+                Some(name) => lookup(&PROFILE_NAMES, &name, "profile")?,
+                None => config.profile,
+            };
+            signature(&run, &export, profile)
+        },
+    );
+    let run = Arc::clone(&shared);
+    env.add_function("tree", move || render_tree_text(&run, config));
+    let run = Arc::clone(&shared);
+    let section = move |name: String| -> Result<String, Error> {
+        let section = lookup(&SECTION_NAMES, &name, "section")?;
+        Ok(render_section_text(&run, config, section))
+    };
+    env.add_function("section", section.clone());
+    env.add_filter("section", section);
+    let run = Arc::clone(&shared);
+    env.add_function(
+        "groups",
+        move |name: String, title: Option<String>| -> Result<Value, Error> {
+            let section = lookup(&SECTION_NAMES, &name, "section")?;
+            Ok(Value::from_serialize(section_groups(
+                &run,
+                config,
+                section,
+                title.as_deref(),
+            )))
+        },
+    );
+
+    let profile = name_of(&PROFILE_NAMES, config.profile);
+    let sections = config
+        .sections
+        .iter()
+        .filter_map(|section| name_of(&SECTION_NAMES, section))
+        .collect::<Vec<_>>();
+    let ctx = context! {
+        profile,
+        sections,
+        query_titles => section_query_titles(output, config),
+        config => context! {
+            include_types => config.include_types,
+            hide_internal => config.hide_internal,
+            hide_deprecated => config.hide_deprecated,
+            grouping => name_of(&GROUPING_NAMES, config.grouping),
+        },
+        ..Value::from_serialize(output)
+    };
+    env.render_str(template, ctx).map_err(|err| err.to_string())
+}
+
+fn name_of<T: PartialEq>(names: &[(&'static str, T)], value: T) -> Option<&'static str> {
+    names
+        .iter()
+        .find(|(_, named)| *named == value)
+        .map(|(name, _)| *name)
+}
+
+fn lookup<T: Copy>(names: &[(&str, T)], name: &str, what: &str) -> Result<T, Error> {
+    names
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, value)| *value)
+        .ok_or_else(|| {
+            let known = names.iter().map(|(known, _)| *known).collect::<Vec<_>>();
+            Error::new(
+                ErrorKind::InvalidOperation,
+                format!(
+                    "unknown {what} `{name}`, expected one of: {}",
+                    known.join(", ")
+                ),
+            )
+        })
+}
+
+/// Finds the export a template value was serialised from, by file, line and
+/// name, and renders its entry line.
+fn signature(output: &RunOutput, export: &Value, profile: RenderProfile) -> Result<String, Error> {
+    let attr = |name: &str| {
+        export
+            .get_attr(name)
+            .ok()
+            .filter(|value| !value.is_undefined())
+    };
+    let file_path = attr("file_path").and_then(|value| value.as_str().map(str::to_string));
+    let line = attr("line").and_then(|value| usize::try_from(value).ok());
+    let name = attr("name").and_then(|value| value.as_str().map(str::to_string));
+    let (Some(file_path), Some(line), Some(name)) = (file_path, line, name) else {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            "signature() expects an export from `file_results`",
+        ));
+    };
+
+    let file = output
+        .file_results
+        .iter()
+        .find(|f| f.file_path == file_path);
+    let rendered = file.and_then(|file| {
+        if attr("overloads").is_some() {
+            file.function_exports
+                .iter()
+                .find(|export| export.line == line && export.name == name)
+                .map(|export| function_line(export, profile))
+        } else if attr("type_annotation").is_some() {
+            file.component_props
+                .iter()
+                .find(|prop| prop.line == line && prop.name == name)
+                .map(|prop| format!("- `{}`", prop_entry(prop, profile)))
+        } else if attr("section").is_some() {
+            file.custom_exports
+                .iter()
+                .find(|custom| custom.line == line && custom.name == name)
+                .map(|custom| custom_line(custom, profile))
+        } else {
+            file.type_exports
+                .iter()
+                .find(|ty| ty.line == line && ty.name == name)
+                .map(|ty| type_line(ty, profile))
+        }
+    });
+    rendered.ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!("no export `{name}` at {file_path}:{line}"),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::markdown::render_markdown_with_config;
+    use crate::{
+        FileResult, FunctionExport, Grouping, RenderConfig, RenderProfile, RepoEntry, RunOutput,
+        RunSummary, Section, Sections, TypeExport,
+    };
+
+    use super::{DEFAULT_TEMPLATE, render_template};
+
+    fn output() -> RunOutput {
+        RunOutput {
+            root_path: "/tmp/repo".to_string(),
+            repo_entries: ["src", "src/a.ts"]
+                .into_iter()
+                .map(|path| RepoEntry {
+                    path: path.to_string(),
+                    is_dir: path == "src",
+                    depth: path.split('/').count(),
+                })
+                .collect(),
+            summary: RunSummary {
+                scanned: 1,
+                parsed: 1,
+                parse_failed: 0,
+                exported_functions: 1,
+                exported_types: 1,
+            },
+            file_results: vec![FileResult {
+                file_path: "src/a.ts".to_string(),
+                function_exports: vec![FunctionExport {
+                    name: "label".to_string(),
+                    signature: "export function label(id: string): string".to_string(),
+                    file_path: "src/a.ts".to_string(),
+                    line: 3,
+                    doc: None,
+                    deprecated: None,
+                    overloads: vec![],
+                    ambient: false,
+                    namespace: None,
+                    importance: 0,
+                }],
+                type_exports: vec![TypeExport {
+                    name: "Label".to_string(),
+                    signature: None,
+                    file_path: "src/a.ts".to_string(),
+                    line: 1,
+                    doc: None,
+                    deprecated: None,
+                    ambient: false,
+                    namespace: None,
+                    importance: 0,
+                }],
+                component_props: vec![],
+                custom_exports: vec![],
+                package: None,
+                parse_error: None,
+                importance: 0,
+            }],
+            walk_errors: vec![],
+        }
+    }
+
+    #[test]
+    fn default_template_matches_the_markdown_renderer() {
+        let output = output();
+        for config in [
+            RenderConfig::default(),
+            RenderConfig {
+                profile: RenderProfile::Detailed,
+                include_types: false,
+                sections: Sections::new(&[Section::Summary, Section::Functions, Section::Tree]),
+                ..RenderConfig::default()
+            },
+        ] {
+            assert_eq!(
+                render_template(&output, config, DEFAULT_TEMPLATE).expect("render"),
+                render_markdown_with_config(&output, config)
+            );
+        }
+    }
+
+    #[test]
+    fn default_template_matches_the_markdown_renderer_on_a_scanned_repo() {
+        let temp = tempfile::TempDir::new().expect("temp dir");
+        let root = temp.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().expect("parent")).expect("mkdir");
+            fs::write(path, content).expect("write");
+        };
+        write(
+            ".context-map/queries/slices.scm",
+            "; title: Redux Slices\n(variable_declarator name: (identifier) @name \
+             value: (call_expression function: (identifier) @kind \
+             (#eq? @kind \"createSlice\")))\n",
+        );
+        write(
+            "src/geo.ts",
+            "/** Area of a circle. */\nexport function area(r: number): number;\n\
+             export function area(r: string): number;\nexport function area(r: any) { return r }\n\
+             export namespace Geo {\n  /** @deprecated use area */\n  export function legacy() {}\n\
+             export interface Point { x: number }\n}\n\
+             /** @internal */\nexport type Secret = string;\n\
+             /**\n * @internal\n * @deprecated use area\n */\nexport function circle() {}\n",
+        );
+        write("src/store.ts", "export const cart = createSlice({ name: \"cart\" });\n");
+        write("src/ui/Card.svelte", "<script>\n  export let title = \"x\";\n</script>\n");
+        write("src/broken.ts", "export function ok() {}\nexport const bad = (;\n");
+        write("svc/api/server.go", "package api\n\n// Serve runs.\nfunc Serve() {}\n");
+        write("svc/api/types.go", "package api\n\ntype Handler interface{}\n");
+        let output = crate::generate_context_map(root).expect("generate");
+        let full = render_markdown_with_config(
+            &output,
+            RenderConfig {
+                sections: Sections::new(&Section::ALL),
+                hide_deprecated: true,
+                ..RenderConfig::default()
+            },
+        );
+        for expected in ["# Redux Slices", "2 deprecated exports", "## Parse Errors", "title"] {
+            assert!(full.contains(expected), "missing {expected:?} in\n{full}");
+        }
+
+        let all = Sections::new(&Section::ALL);
+        for grouping in [Grouping::File, Grouping::Directory, Grouping::Symbol] {
+            for profile in [
+                RenderProfile::Compact,
+                RenderProfile::Balanced,
+                RenderProfile::Detailed,
+            ] {
+                for (hide_internal, hide_deprecated, include_types) in
+                    [(false, false, true), (true, true, true), (false, true, false)]
+                {
+                    let config = RenderConfig {
+                        profile,
+                        grouping,
+                        hide_internal,
+                        hide_deprecated,
+                        include_types,
+                        sections: all,
+                        ..RenderConfig::default()
+                    };
+                    let markdown = render_markdown_with_config(&output, config);
+                    assert_eq!(
+                        render_template(&output, config, DEFAULT_TEMPLATE).expect("render"),
+                        markdown,
+                        "{config:?}"
+                    );
+                    if hide_internal {
+                        assert!(markdown.contains("<summary>1 deprecated export</summary>"));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exposes_the_model_and_helpers() {
+        let template = "\
+{{ summary.scanned }} files under {{ root_path }}
+{% for file in file_results %}## {{ file.file_path }}
+{% for export in file.function_exports %}{{ signature(export) }} / {{ signature(export, \"detailed\") }}
+{% endfor %}{% for ty in file.type_exports %}{{ signature(ty) }}
+{% endfor %}{% endfor %}{{ tree() }}
+";
+        let rendered =
+            render_template(&output(), RenderConfig::default(), template).expect("render");
+
+        assert_eq!(
+            rendered,
+            "1 files under /tmp/repo\n## src/a.ts\n\
+             - `label(id: string)` / - `export function label(id: string): string @L3`\n\
+             - `Label`\n\
+             .\n└── src (1 function, 1 type)\n    └── a.ts (1 function, 1 type)\n"
+        );
+    }
+
+    #[test]
+    fn reports_template_errors() {
+        let config = RenderConfig::default();
+
+        let err = render_template(&output(), config, "{{ section(\"nope\") }}").expect_err("name");
+        assert!(err.contains("unknown section `nope`"), "{err}");
+        assert!(render_template(&output(), config, "{% for %}").is_err());
+    }
+}
@@ -4,17 +4,56 @@ use std::path::{Path, PathBuf};
 
 use walkdir::{DirEntry, WalkDir};
 
+use crate::language::ExtractorRegistry;
+
+/// The TypeScript-family file kinds handled by `TsExportParser`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SourceKind {
     Ts,
     Tsx,
     Vue,
+    Svelte,
+    Astro,
+    Mdx,
+    /// `.d.ts` declaration file; only collected when declarations are opted in.
+    Dts,
+    /// `.js`; may be an ES module or a CommonJS Node script.
+    Js,
+    Jsx,
+    Mjs,
+    Cjs,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SourceFile {
     pub path: PathBuf,
-    pub kind: SourceKind,
+    /// Index of the registry extractor that claimed the file.
+    pub extractor: usize,
+}
+
+/// Source files found under a root, plus the entries the walk could not read.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SourceWalk {
+    pub files: Vec<SourceFile>,
+    pub errors: Vec<WalkError>,
+}
+
+/// Repository tree entries, plus the entries the walk could not read.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RepoWalk {
+    pub entries: Vec<RepoEntry>,
+    pub errors: Vec<WalkError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WalkOptions {
+    pub include_declarations: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -25,7 +64,15 @@ pub struct RepoEntry {
 }
 
 fn ignored_dirs() -> HashSet<&'static str> {
-    [".git", "node_modules", "dist", "build", "target"]
+    [
+        ".git",
+        "node_modules",
+        "dist",
+        "build",
+        "target",
+        "__pycache__",
+        "venv",
+    ]
         .into_iter()
         .collect()
 }
@@ -50,12 +97,14 @@ fn should_descend(entry: &DirEntry) -> bool {
     true
 }
 
-fn classify_source_file(path: &Path) -> Option<SourceKind> {
+pub fn classify_source_file(path: &Path, options: &WalkOptions) -> Option<SourceKind> {
     let ext = path.extension().and_then(|ext| ext.to_str())?;
     match ext {
         "ts" => {
             let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
-            if file_name.ends_with(".d.ts") || file_name.ends_with(".props.ts") {
+            if file_name.ends_with(".d.ts") {
+                options.include_declarations.then_some(SourceKind::Dts)
+            } else if file_name.ends_with(".props.ts") {
                 None
             } else {
                 Some(SourceKind::Ts)
@@ -63,11 +112,43 @@ fn classify_source_file(path: &Path) -> Option<SourceKind> {
         }
         "tsx" => Some(SourceKind::Tsx),
         "vue" => Some(SourceKind::Vue),
+        "svelte" => Some(SourceKind::Svelte),
+        "astro" => Some(SourceKind::Astro),
+        "mdx" => Some(SourceKind::Mdx),
+        "js" => {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            if file_name.ends_with(".min.js") {
+                None
+            } else {
+                Some(SourceKind::Js)
+            }
+        }
+        "jsx" => Some(SourceKind::Jsx),
+        "mjs" => Some(SourceKind::Mjs),
+        "cjs" => Some(SourceKind::Cjs),
         _ => None,
     }
 }
 
 pub fn collect_source_files(root: &Path) -> io::Result<Vec<SourceFile>> {
+    collect_source_files_with_options(root, &WalkOptions::default()).map(|walk| walk.files)
+}
+
+/// Walks with the built-in extractors; see [`collect_source_files_with_registry`].
+pub fn collect_source_files_with_options(
+    root: &Path,
+    options: &WalkOptions,
+) -> io::Result<SourceWalk> {
+    let registry = ExtractorRegistry::builtin().map_err(io::Error::other)?;
+    collect_source_files_with_registry(root, options, &registry)
+}
+
+/// Collects every file some extractor in `registry` claims.
+pub fn collect_source_files_with_registry(
+    root: &Path,
+    options: &WalkOptions,
+    registry: &ExtractorRegistry,
+) -> io::Result<SourceWalk> {
     if !root.is_dir() {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
@@ -75,23 +156,40 @@ pub fn collect_source_files(root: &Path) -> io::Result<Vec<SourceFile>> {
         ));
     }
 
-    let mut files = WalkDir::new(root)
-        .into_iter()
-        .filter_entry(should_descend)
-        .filter_map(Result::ok)
-        .filter(|entry| entry.file_type().is_file())
-        .filter_map(|entry| {
-            let path = entry.path().to_path_buf();
-            let kind = classify_source_file(&path)?;
-            Some(SourceFile { path, kind })
-        })
-        .collect::<Vec<_>>();
+    let mut walk = SourceWalk::default();
+    for entry in WalkDir::new(root).into_iter().filter_entry(should_descend) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                walk.errors.push(walk_error(&err, root));
+                continue;
+            }
+        };
 
-    files.sort_by(|a, b| a.path.cmp(&b.path));
-    Ok(files)
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path().to_path_buf();
+        if let Some(extractor) = registry.classify(&path, options) {
+            walk.files.push(SourceFile { path, extractor });
+        }
+    }
+
+    walk.files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(walk)
+}
+
+fn walk_error(err: &walkdir::Error, root: &Path) -> WalkError {
+    WalkError {
+        path: err.path().unwrap_or(root).to_path_buf(),
+        message: err
+            .io_error()
+            .map_or_else(|| err.to_string(), ToString::to_string),
+    }
 }
 
-pub fn collect_repo_entries(root: &Path, max_depth: usize) -> io::Result<Vec<RepoEntry>> {
+pub fn collect_repo_entries(root: &Path, max_depth: usize) -> io::Result<RepoWalk> {
     if !root.is_dir() {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
@@ -99,21 +197,26 @@ pub fn collect_repo_entries(root: &Path, max_depth: usize) -> io::Result<Vec<Rep
         ));
     }
 
-    let mut entries = WalkDir::new(root)
+    let mut walk = RepoWalk::default();
+    for entry in WalkDir::new(root)
         .max_depth(max_depth)
         .into_iter()
         .filter_entry(should_descend)
-        .filter_map(Result::ok)
-        .filter(|entry| entry.depth() > 0)
-        .map(|entry| RepoEntry {
-            path: entry.path().to_path_buf(),
-            is_dir: entry.file_type().is_dir(),
-            depth: entry.depth(),
-        })
-        .collect::<Vec<_>>();
+    {
+        match entry {
+            Ok(entry) if entry.depth() > 0 => walk.entries.push(RepoEntry {
+                path: entry.path().to_path_buf(),
+                is_dir: entry.file_type().is_dir(),
+                depth: entry.depth(),
+            }),
+            Ok(_) => {}
+            Err(err) => walk.errors.push(walk_error(&err, root)),
+        }
+    }
 
-    entries.sort_by(|a, b| a.path.cmp(&b.path).then(a.is_dir.cmp(&b.is_dir).reverse()));
-    Ok(entries)
+    walk.entries
+        .sort_by(|a, b| a.path.cmp(&b.path).then(a.is_dir.cmp(&b.is_dir).reverse()));
+    Ok(walk)
 }
 
 #[cfg(test)]
@@ -122,7 +225,12 @@ mod tests {
 
     use tempfile::TempDir;
 
-    use super::{collect_repo_entries, collect_source_files};
+    use super::{
+        SourceKind, WalkOptions, classify_source_file, collect_repo_entries,
+        collect_source_files, collect_source_files_with_options,
+        collect_source_files_with_registry,
+    };
+    use crate::language::ExtractorRegistry;
 
     #[test]
     fn skips_ignored_dirs_and_finds_nested_sources() {
@@ -138,6 +246,14 @@ mod tests {
             .expect("write tsx");
         fs::write(root.join("src/comp.vue"), "<template/>\n<script>export function v() {}</script>\n")
             .expect("write vue");
+        fs::write(root.join("src/Card.svelte"), "<script>export let title;</script>\n")
+            .expect("write svelte");
+        fs::write(root.join("src/page.astro"), "---\nexport const x = () => 1;\n---\n")
+            .expect("write astro");
+        fs::write(root.join("src/guide.mdx"), "export const y = () => 1;\n").expect("write mdx");
+        fs::write(root.join("src/app.py"), "def run(): ...\n").expect("write py");
+        fs::write(root.join("src/lib.rs"), "pub fn run() {}\n").expect("write rs");
+        fs::write(root.join("src/main.go"), "package main\n").expect("write go");
         fs::write(root.join("src/nested/util.ts"), "export function nested() {}\n")
             .expect("write nested");
         fs::write(root.join("src/types.d.ts"), "declare const x: string\n")
@@ -148,6 +264,12 @@ mod tests {
             .expect("write props tsx");
         fs::write(root.join("node_modules/pkg/nope.ts"), "export function nope() {}\n")
             .expect("write ignored");
+        fs::write(root.join("src/legacy.js"), "exports.a = () => 1;\n").expect("write js");
+        fs::write(root.join("src/legacy.min.js"), "exports.a=()=>1;\n").expect("write min js");
+        fs::write(root.join("src/widget.jsx"), "export const W = () => <i />;\n")
+            .expect("write jsx");
+        fs::write(root.join("src/esm.mjs"), "export function m() {}\n").expect("write mjs");
+        fs::write(root.join("src/node.cjs"), "module.exports = {};\n").expect("write cjs");
 
         let files = collect_source_files(root).expect("collect files");
         let paths = files
@@ -164,15 +286,62 @@ mod tests {
         assert_eq!(
             paths,
             vec![
+                "src/Card.svelte",
+                #[cfg(feature = "python")]
+                "src/app.py",
                 "src/card.props.tsx",
                 "src/comp.vue",
+                "src/esm.mjs",
+                "src/guide.mdx",
                 "src/index.ts",
+                "src/legacy.js",
+                #[cfg(feature = "rust")]
+                "src/lib.rs",
+                #[cfg(feature = "go")]
+                "src/main.go",
                 "src/nested/util.ts",
-                "src/view.tsx"
+                "src/node.cjs",
+                "src/page.astro",
+                "src/view.tsx",
+                "src/widget.jsx"
             ]
         );
     }
 
+    #[test]
+    fn includes_declaration_files_when_opted_in() {
+        let temp = TempDir::new().expect("temp dir");
+        let root = temp.path();
+
+        fs::write(root.join("index.ts"), "export function ok() {}\n").expect("write index");
+        fs::write(root.join("types.d.ts"), "declare const x: string\n").expect("write dts");
+
+        let options = WalkOptions {
+            include_declarations: true,
+        };
+        let registry = ExtractorRegistry::builtin().expect("registry");
+        let files =
+            collect_source_files_with_registry(root, &options, &registry).expect("collect files");
+        let kinds = files
+            .files
+            .iter()
+            .map(|f| {
+                let kind = classify_source_file(&f.path, &options).expect("kind");
+                (kind, registry.names()[f.extractor])
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![(SourceKind::Ts, "typescript"), (SourceKind::Dts, "typescript")]
+        );
+        assert!(files.errors.is_empty());
+
+        let default = collect_source_files_with_options(root, &WalkOptions::default())
+            .expect("collect files");
+        assert_eq!(default.files.len(), 1);
+    }
+
     #[test]
     fn collects_repo_entries_with_depth_limit() {
         let temp = TempDir::new().expect("temp dir");
@@ -185,8 +354,10 @@ mod tests {
         fs::write(root.join("a/b/c/d/too-deep.txt"), "no\n").expect("write too deep");
         fs::write(root.join("node_modules/pkg/x.txt"), "no\n").expect("write ignored");
 
-        let entries = collect_repo_entries(root, 3).expect("collect entries");
-        let paths = entries
+        let walk = collect_repo_entries(root, 3).expect("collect entries");
+        assert!(walk.errors.is_empty());
+        let paths = walk
+            .entries
             .iter()
             .map(|e| {
                 e.path
@@ -1,6 +1,13 @@
 use std::fs;
+use std::path::Path;
 
-use context_map::{RenderConfig, RenderProfile};
+use context_map::language::{ExtractorRegistry, LanguageExtractor};
+use context_map::parser::{ExtractedExports, ExtractedType};
+use context_map::walker::WalkOptions;
+use context_map::{
+    ContextMapError, RenderConfig, RenderProfile, ScanConfig, Section, Sections, SortOrder,
+    SplitMode, generate_context_map_with_registry,
+};
 
 #[test]
 fn integration_handles_valid_and_invalid_files() {
@@ -38,16 +45,21 @@ fn integration_handles_valid_and_invalid_files() {
         .expect("write props tsx");
 
     fs::write(root.join("src/invalid.ts"), "export function bad( {\n").expect("write invalid");
+    fs::write(
+        root.join("src/partial.ts"),
+        "export function intact(id: string) {}\nexport const broken = (;\n",
+    )
+    .expect("write partial");
 
     fs::write(root.join("dist/ignored.ts"), "export function ignored() {}\n")
         .expect("write ignored");
 
     let result = context_map::generate_context_map(root).expect("generate");
 
-    assert_eq!(result.summary.scanned, 6);
+    assert_eq!(result.summary.scanned, 7);
     assert_eq!(result.summary.parsed, 5);
-    assert_eq!(result.summary.parse_failed, 1);
-    assert_eq!(result.summary.exported_functions, 5);
+    assert_eq!(result.summary.parse_failed, 2);
+    assert_eq!(result.summary.exported_functions, 6);
     assert_eq!(result.summary.exported_types, 2);
 
     let md_compact = context_map::markdown::render_markdown_with_config(
@@ -55,7 +67,7 @@ fn integration_handles_valid_and_invalid_files() {
         RenderConfig {
             profile: RenderProfile::Compact,
             include_types: true,
-            tree_depth: 10,
+            ..RenderConfig::default()
         },
     );
     assert!(md_compact.contains("# Repository Structure"));
@@ -69,7 +81,7 @@ fn integration_handles_valid_and_invalid_files() {
         RenderConfig {
             profile: RenderProfile::Balanced,
             include_types: true,
-            tree_depth: 10,
+            ..RenderConfig::default()
         },
     );
     assert!(md_balanced.contains("- `hello(name: string)`"));
@@ -82,7 +94,7 @@ fn integration_handles_valid_and_invalid_files() {
         RenderConfig {
             profile: RenderProfile::Detailed,
             include_types: true,
-            tree_depth: 10,
+            ..RenderConfig::default()
         },
     );
     assert!(md_detailed.contains("@L"));
@@ -93,11 +105,310 @@ fn integration_handles_valid_and_invalid_files() {
         RenderConfig {
             profile: RenderProfile::Balanced,
             include_types: false,
-            tree_depth: 10,
+            ..RenderConfig::default()
         },
     );
     assert!(!md_no_types.contains("# Type Inventory"));
 
     assert!(md_balanced.contains("## Parse Errors"));
+    assert!(md_balanced.contains("- `intact(id: string)`"));
+    assert!(md_balanced.contains("- `src/partial.ts`: syntax parse error\n  - L2:"));
+
+    let partial = result
+        .file_results
+        .iter()
+        .find(|f| f.file_path == "src/partial.ts")
+        .and_then(|f| f.parse_error.as_ref())
+        .expect("partial error");
+    assert_eq!(partial.diagnostics[0].snippet, "export const broken = (;");
     assert!(!md_balanced.contains("dist"));
 }
+
+#[test]
+fn strict_mode_fails_on_parse_and_read_errors() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).expect("mkdir src");
+    fs::write(root.join("src/ok.ts"), "export function ok() {}\n").expect("write ok");
+    fs::write(root.join("src/invalid.ts"), "export function bad( {\n").expect("write invalid");
+
+    let strict = ScanConfig {
+        strict: true,
+        ..ScanConfig::default()
+    };
+    let config = RenderConfig::default();
+
+    let lenient =
+        context_map::generate_context_map_with_config(root, ScanConfig::default(), config)
+            .expect("lenient run succeeds");
+    assert_eq!(lenient.summary.parse_failed, 1);
+
+    let err = context_map::generate_context_map_with_config(root, strict, config)
+        .expect_err("parse");
+    assert!(matches!(err, ContextMapError::Parse { .. }));
+    assert!(err.to_string().contains("invalid.ts"));
+    assert!(err.to_string().contains("syntax parse error at 1:"));
+
+    fs::remove_file(root.join("src/invalid.ts")).expect("remove invalid");
+    fs::write(root.join("src/binary.ts"), [0xff, 0xfe, 0x00]).expect("write binary");
+
+    let err = context_map::generate_context_map_with_config(root, strict, config)
+        .expect_err("read");
+    assert!(matches!(err, ContextMapError::Read { .. }));
+    assert!(err.to_string().contains("binary.ts"));
+}
+
+#[test]
+fn write_errors_name_the_output_path() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("missing-dir/REPO.md");
+
+    let err = context_map::run(temp.path(), &out).expect_err("write fails");
+
+    assert!(matches!(err, ContextMapError::Write { .. }));
+    assert!(err.to_string().contains("missing-dir/REPO.md"));
+}
+
+struct TodoExtractor;
+
+impl LanguageExtractor for TodoExtractor {
+    fn name(&self) -> &str {
+        "todo"
+    }
+
+    fn handles(&self, path: &Path, _options: &WalkOptions) -> bool {
+        path.extension().is_some_and(|ext| ext == "todo")
+    }
+
+    fn extract(&mut self, source: &str, _path: &Path) -> Result<ExtractedExports, String> {
+        let types = source
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| ExtractedType {
+                name: line.trim().to_string(),
+                line: idx + 1,
+                ..ExtractedType::default()
+            })
+            .collect();
+        Ok(ExtractedExports {
+            types,
+            ..ExtractedExports::default()
+        })
+    }
+}
+
+#[test]
+fn custom_extractors_join_the_run() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::write(root.join("index.ts"), "export function ok() {}\n").expect("write ts");
+    fs::write(root.join("plan.todo"), "Ship\nReview\n").expect("write todo");
+
+    let mut registry = ExtractorRegistry::builtin().expect("registry");
+    registry.register(TodoExtractor);
+    let output = generate_context_map_with_registry(
+        root,
+        ScanConfig::default(),
+        RenderConfig::default(),
+        &mut registry,
+    )
+        .expect("generate");
+
+    assert_eq!(output.summary.scanned, 2);
+    assert_eq!(output.summary.exported_types, 2);
+    let todo = output
+        .file_results
+        .iter()
+        .find(|file| file.file_path == "plan.todo")
+        .expect("todo file");
+    assert_eq!(todo.type_exports[1].name, "Review");
+}
+
+#[test]
+fn user_queries_add_custom_sections() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::create_dir_all(root.join(".context-map/queries")).expect("mkdir queries");
+    fs::write(
+        root.join(".context-map/queries/slices.scm"),
+        "; title: Redux Slices\n(variable_declarator name: (identifier) @name value: (call_expression function: (identifier) @kind (#eq? @kind \"createSlice\")))\n",
+    )
+    .expect("write query");
+    fs::write(
+        root.join("store.ts"),
+        "const local = 1;\nexport const cart = createSlice({ name: \"cart\" });\n",
+    )
+    .expect("write ts");
+
+    let output = context_map::generate_context_map(root).expect("generate");
+    let custom = &output.file_results[0].custom_exports;
+    assert_eq!(custom.len(), 1);
+    assert_eq!(custom[0].section, "Redux Slices");
+    assert_eq!(custom[0].name, "cart");
+    assert_eq!(custom[0].line, 2);
+
+    let markdown = context_map::markdown::render_markdown(&output);
+    assert!(markdown.contains("# Redux Slices\n\n### `store.ts`\n- `cart` _createSlice_"));
+
+    fs::write(root.join(".context-map/queries/broken.scm"), "(nope) @name\n").expect("write bad");
+    let err = context_map::generate_context_map(root).expect_err("invalid query");
+    assert!(err.to_string().contains("invalid query"));
+}
+
+#[test]
+fn importance_orders_sections_and_reaches_json() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::create_dir_all(root.join("src/lib")).expect("mkdir");
+    fs::write(root.join("src/a.ts"), "export function leaf() {}\n").expect("write a");
+    fs::write(
+        root.join("src/lib/core.ts"),
+        "export function raw() {}\n/** Shared helper. */\nexport function core() {}\n",
+    )
+    .expect("write core");
+    fs::write(root.join("src/lib/index.ts"), "export * from \"./core\";\n").expect("write index");
+    fs::write(
+        root.join("src/main.ts"),
+        "import { core } from \"./lib\";\nimport { core as c } from \"./lib/core.js\";\nexport function main() {}\n",
+    )
+    .expect("write main");
+
+    // Markdown-only runs skip ranking unless the render consumes the scores.
+    let unranked = context_map::generate_context_map(root).expect("generate");
+    assert!(unranked.file_results.iter().all(|f| f.importance == 0));
+
+    let config = RenderConfig {
+        sort: SortOrder::Importance,
+        ..RenderConfig::default()
+    };
+    let output = context_map::generate_context_map_with_config(root, ScanConfig::default(), config)
+        .expect("generate");
+    let core = output
+        .file_results
+        .iter()
+        .find(|f| f.file_path == "src/lib/core.ts")
+        .expect("core");
+    // Imported by the barrel and main.ts, re-exported, documented.
+    assert_eq!(core.importance, 2 * 3 + 4 + 2);
+    let scores = core
+        .function_exports
+        .iter()
+        .map(|export| (export.name.as_str(), export.importance))
+        .collect::<Vec<_>>();
+    assert_eq!(scores, vec![("raw", 2 * 3 + 4), ("core", 2 * 3 + 4 + 2)]);
+
+    let json = context_map::json::render_json(&output);
+    assert!(json.contains("\"importance\": 12"));
+
+    // A plain JSON run ranks too, so the exposed scores are never a stand-in 0.
+    let out = root.join("REPO.json");
+    let json_only = RenderConfig {
+        format: context_map::OutputFormat::Json,
+        ..RenderConfig::default()
+    };
+    context_map::run_with_config(root, &out, ScanConfig::default(), json_only).expect("json run");
+    assert!(fs::read_to_string(&out).expect("read json").contains("\"importance\": 12"));
+
+    let markdown = context_map::markdown::render_markdown_with_config(&output, config);
+    let core_at = markdown.find("### `src/lib/core.ts`").expect("core section");
+    let leaf_at = markdown.find("### `src/a.ts`").expect("leaf section");
+    assert!(core_at < leaf_at);
+    assert!(markdown.contains("### `src/lib/core.ts`\n- `core()`\n- `raw()`\n"));
+}
+
+#[test]
+fn switching_back_to_one_file_removes_split_parts() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::create_dir_all(root.join("api")).expect("mkdir");
+    fs::write(root.join("api/handler.ts"), "export function handle() {}\n").expect("write api");
+    let out = root.join("REPO.md");
+
+    let split = RenderConfig {
+        split: SplitMode::Directory,
+        ..RenderConfig::default()
+    };
+    context_map::run_with_config(root, &out, ScanConfig::default(), split).expect("split run");
+    assert!(root.join("api/CONTEXT.md").exists());
+
+    context_map::run_with_config(root, &out, ScanConfig::default(), RenderConfig::default())
+        .expect("single run");
+    assert!(!root.join("api/CONTEXT.md").exists());
+    let index = fs::read_to_string(&out).expect("read out");
+    assert!(index.contains("### `api/handler.ts`"));
+}
+
+#[test]
+fn inject_fills_the_managed_block_with_selected_sections() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::write(root.join("lib.ts"), "export function greet() {}\n").expect("write lib");
+    let agents = root.join("AGENTS.md");
+    fs::write(&agents, "# Agents\n\nUse pnpm.\n").expect("write agents");
+
+    let functions_only = RenderConfig {
+        sections: Sections::new(&[Section::Functions]),
+        ..RenderConfig::default()
+    };
+    context_map::inject_with_config(root, &agents, ScanConfig::default(), functions_only)
+        .expect("inject");
+
+    let document = fs::read_to_string(&agents).expect("read agents");
+    assert!(document.starts_with("# Agents\n\nUse pnpm.\n\n<!-- context-map:start -->\n"));
+    assert!(document.contains("# Exported Functions\n\n### `lib.ts`\n- `greet()`\n"));
+    assert!(!document.contains("# Repository Structure"));
+    assert!(document.ends_with("<!-- context-map:end -->\n"));
+
+    fs::write(&agents, "<!-- context-map:start -->\nno end\n").expect("break markers");
+    let err = context_map::inject_with_config(
+        root,
+        &agents,
+        ScanConfig::default(),
+        RenderConfig::default(),
+    )
+    .expect_err("unbalanced markers");
+    assert!(matches!(err, ContextMapError::Inject { .. }));
+}
+
+#[cfg(feature = "templates")]
+#[test]
+fn templates_reject_configs_they_cannot_honour() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let root = temp.path();
+    fs::write(root.join("lib.ts"), "export function greet() {}\n").expect("write lib");
+    let template = root.join("map.jinja");
+    fs::write(&template, "{{ summary.exported_functions }}\n").expect("write template");
+    let out = root.join("REPO.md");
+
+    let unsupported = [
+        RenderConfig {
+            max_tokens: Some(500),
+            ..RenderConfig::default()
+        },
+        RenderConfig {
+            split: SplitMode::Directory,
+            ..RenderConfig::default()
+        },
+        RenderConfig {
+            format: context_map::OutputFormat::Json,
+            ..RenderConfig::default()
+        },
+    ];
+    for config in unsupported {
+        let err =
+            context_map::run_with_template(root, &out, ScanConfig::default(), config, &template)
+                .expect_err("unsupported config");
+        assert!(matches!(err, ContextMapError::Template { .. }), "{config:?}");
+    }
+    assert!(!out.exists());
+
+    context_map::run_with_template(
+        root,
+        &out,
+        ScanConfig::default(),
+        RenderConfig::default(),
+        &template,
+    )
+    .expect("template run");
+    assert_eq!(fs::read_to_string(&out).expect("read out"), "1\n");
+}